@@ -17,7 +17,10 @@ use bsp::{
     pac::{CorePeripherals, Peripherals},
 };
 use panic_semihosting as _;
-use quinti_maze::game::NOTES;
+use quinti_maze::{
+    game::NOTES,
+    sequencer::{Sequencer, ToneAction},
+};
 use rtt_target::{rprintln, rtt_init_print};
 
 #[entry]
@@ -48,20 +51,24 @@ fn main() -> ! {
     pwm.disable(Channel::_0);
 
     let mut delayer = Delay::new(core.SYST, &mut clocks);
-    for note in NOTES {
-        rprintln!(
-            "freq, duration, delay = {}, {}, {}",
-            note.frequency,
-            note.duration,
-            note.delay
-        );
-        delayer.delay_ms(note.delay as u32);
-        pwm.set_period((note.frequency as u32).hz());
-        let max_duty = pwm.get_max_duty();
-        pwm.set_duty(Channel::_0, max_duty / 2);
-        pwm.enable(Channel::_0);
-        delayer.delay_ms(note.duration as u32);
-        pwm.disable(Channel::_0);
+    let mut sequencer = Sequencer::default();
+    let mut now = 0;
+    sequencer.play(NOTES, now);
+    while let Some(due) = sequencer.next_due() {
+        delayer.delay_ms((due - now) as u32);
+        now = due;
+        while let Some(action) = sequencer.update(now) {
+            rprintln!("{} ms: {:?}", now, action);
+            match action {
+                ToneAction::On { frequency } => {
+                    pwm.set_period((frequency as u32).hz());
+                    let max_duty = pwm.get_max_duty();
+                    pwm.set_duty(Channel::_0, max_duty / 2);
+                    pwm.enable(Channel::_0);
+                }
+                ToneAction::Off => pwm.disable(Channel::_0),
+            }
+        }
     }
     pwm.disable(Channel::_0);
 
@@ -14,7 +14,10 @@ mod app {
     use hal::pac::Peripherals;
     use hal::prelude::*;
     use hal::pwm::{Channel, TCC2Pinout, Tcc2Pwm};
-    use quinti_maze::game::NOTES;
+    use quinti_maze::{
+        game::NOTES,
+        sequencer::{Sequencer, ToneAction},
+    };
     use rtt_target::{rprintln, rtt_init_print};
     use systick_monotonic::*;
 
@@ -24,6 +27,7 @@ mod app {
     #[shared]
     struct Shared {
         pwm: Tcc2Pwm<PA14, hal::gpio::Alternate<F>>,
+        sequencer: Sequencer,
     }
 
     #[monotonic(binds = SysTick, default = true)]
@@ -61,44 +65,54 @@ mod app {
         pwm.set_duty(Channel::_0, max_duty / 4);
         pwm.disable(Channel::_0);
 
-        // Start the blink task
-        play_note_with_delay::spawn(0).ok();
+        // Start the tune
+        play_tune::spawn().ok();
+
+        (
+            Shared {
+                pwm,
+                sequencer: Sequencer::default(),
+            },
+            Local {},
+            init::Monotonics(mono),
+        )
+    }
 
-        (Shared { pwm }, Local {}, init::Monotonics(mono))
+    fn monotonic_millis() -> u64 {
+        monotonics::now().duration_since_epoch().to_millis()
     }
 
-    #[task]
-    fn play_note_with_delay(_cx: play_note_with_delay::Context, index: usize) {
-        if index < NOTES.len() {
-            let note = &NOTES[index];
-            rprintln!("play_note_with_delay {}:{:#?}", index, note);
-            if note.delay > 0 {
-                start_note::spawn_after(note.delay.millis(), index).ok();
-            } else {
-                start_note::spawn(index).ok();
-            }
-        }
+    #[task(shared = [sequencer])]
+    fn play_tune(mut cx: play_tune::Context) {
+        let now = monotonic_millis();
+        cx.shared
+            .sequencer
+            .lock(|sequencer| sequencer.play(NOTES, now));
+        sound::spawn().ok();
     }
 
-    #[task(shared = [pwm])]
-    fn start_note(mut cx: start_note::Context, index: usize) {
-        if index < NOTES.len() {
-            let note = &NOTES[index];
+    #[task(shared = [pwm, sequencer])]
+    fn sound(mut cx: sound::Context) {
+        let now = monotonic_millis();
+        let next_due = cx.shared.sequencer.lock(|sequencer| {
             cx.shared.pwm.lock(|pwm| {
-                pwm.set_period((note.frequency as u32).hz());
-                let max_duty = pwm.get_max_duty();
-                pwm.set_duty(Channel::_0, max_duty / 2);
-                pwm.enable(Channel::_0);
+                while let Some(action) = sequencer.update(now) {
+                    rprintln!("{} ms: {:?}", now, action);
+                    match action {
+                        ToneAction::On { frequency } => {
+                            pwm.set_period((frequency as u32).hz());
+                            let max_duty = pwm.get_max_duty();
+                            pwm.set_duty(Channel::_0, max_duty / 2);
+                            pwm.enable(Channel::_0);
+                        }
+                        ToneAction::Off => pwm.disable(Channel::_0),
+                    }
+                }
             });
-            end_note::spawn_after((note.duration as u64).millis(), index).ok();
-        }
-    }
-
-    #[task(shared = [pwm])]
-    fn end_note(mut cx: end_note::Context, index: usize) {
-        cx.shared.pwm.lock(|pwm| {
-            pwm.disable(Channel::_0);
+            sequencer.next_due()
         });
-        play_note_with_delay::spawn_after(10.millis(), index + 1).ok();
+        if let Some(due) = next_due {
+            sound::spawn_after((due - now).millis()).ok();
+        }
     }
 }
@@ -12,6 +12,9 @@ const KEYS: &[&[char]] = &[
     &['*', '0', '#'],
 ];
 
+/// Scans, ten milliseconds apart, that a key is held for a long press.
+const LONG_PRESS_SCANS: u16 = 100;
+
 #[rtic::app(device = bsp::pac, peripherals = true, dispatchers = [EVSYS_0, EVSYS_1, EVSYS_2])]
 mod app {
     use super::*;
@@ -27,7 +30,11 @@ mod app {
 
     use ili9341::{DisplaySize240x320, Ili9341, Orientation};
 
-    use quinti_maze::game::{Command, Game, PlatformSpecific, NOTES};
+    use quinti_maze::{
+        game::{Game, Input, Key, PlatformSpecific, SoundEvent},
+        keys::{KeyBindings, KeyId, Layout},
+        sequencer::{Sequencer, ToneAction},
+    };
     use rtt_target::{rprintln, rtt_init_print};
     use systick_monotonic::*;
 
@@ -37,8 +44,8 @@ mod app {
     pub struct DevicePlatform;
 
     impl PlatformSpecific for DevicePlatform {
-        fn play_victory_notes(&mut self) {
-            play_note_with_delay::spawn(0).ok();
+        fn play_sound(&mut self, event: SoundEvent) {
+            start_sound::spawn(event).ok();
         }
 
         fn ticks(&mut self) -> u64 {
@@ -85,12 +92,20 @@ mod app {
         cols: [DynPin; 3],
         rows: [DynPin; 4],
         debouncers: [KeyDebouncer; 12],
+        /// Scans each key has been held down for.
+        held: [u16; 12],
+        bindings: KeyBindings,
     }
 
     #[shared]
     struct Shared {
         game: Game<DevicePlatform>,
         pwm: Tcc2Pwm<PA14, hal::gpio::Alternate<F>>,
+        /// The next redraw, which a key can bring forward.
+        next_render: Option<render_game::SpawnHandle>,
+        sequencer: Sequencer,
+        /// The next tone action, replaced when another sound starts.
+        next_sound: Option<sound::SpawnHandle>,
     }
 
     #[monotonic(binds = SysTick, default = true)]
@@ -193,28 +208,37 @@ mod app {
         render_game::spawn().unwrap();
 
         (
-            Shared { game, pwm },
+            Shared {
+                game,
+                pwm,
+                next_render: None,
+                sequencer: Sequencer::default(),
+                next_sound: None,
+            },
             Local {
                 lcd,
                 cols,
                 rows,
                 debouncers,
+                held: [0; 12],
+                bindings: KeyBindings::new(Layout::Keypad),
             },
             init::Monotonics(mono),
         )
     }
 
-    #[task(local = [lcd], shared = [game])]
+    #[task(local = [lcd], shared = [game, next_render])]
     fn render_game(mut cx: render_game::Context) {
         cx.shared.game.lock(|game| {
             if let Err(e) = game.draw(cx.local.lcd) {
                 rprintln!("err = {:?}", e);
             }
         });
-        render_game::spawn_after(500.millis()).ok();
+        let next_render = render_game::spawn_after(500.millis()).ok();
+        cx.shared.next_render.lock(|handle| *handle = next_render);
     }
 
-    #[task(priority = 1, local = [rows, cols, debouncers], shared = [game])]
+    #[task(priority = 1, local = [rows, cols, debouncers, held, bindings], shared = [game, next_render])]
     fn scan(mut cx: scan::Context) {
         for (row_index, row) in cx.local.rows.iter_mut().enumerate() {
             row.into_push_pull_output();
@@ -227,49 +251,36 @@ mod app {
                     rprintln!("is_low failed");
                     false
                 });
-                let edge = cx.local.debouncers[index].update(col_value);
-                if Some(Edge::Rising) == edge {
-                    let more_processing = cx.shared.game.lock(|game| game.key_hit());
-                    if more_processing {
-                        match KEYS[row_index][col_index] {
-                            '1' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::MoveDown)),
-                            '2' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::MoveForward)),
-                            '3' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::MoveUp)),
-                            '4' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::MoveLeft)),
-                            '6' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::MoveRight)),
-                            '7' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::TurnLeft)),
-                            '9' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::TurnRight)),
-                            '*' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::ToggleShowPosition)),
-                            '#' => cx
-                                .shared
-                                .game
-                                .lock(|game| game.handle_command(Command::ShowHints)),
-                            _ => (),
-                        }
+                let debouncer = &mut cx.local.debouncers[index];
+                let edge = debouncer.update(col_value);
+                let held = &mut cx.local.held[index];
+                let key = Key {
+                    command: cx
+                        .local
+                        .bindings
+                        .command(KeyId::character(KEYS[row_index][col_index])),
+                    character: None,
+                };
+                let input = match edge {
+                    Some(Edge::Rising) => {
+                        *held = 0;
+                        Some(Input::KeyDown(key))
+                    }
+                    Some(Edge::Falling) => Some(Input::KeyUp(key)),
+                    None if debouncer.is_high() => {
+                        *held = held.saturating_add(1);
+                        (*held == LONG_PRESS_SCANS).then_some(Input::LongPress(key))
+                    }
+                    None => None,
+                };
+                if let Some(input) = input {
+                    let outcome = cx.shared.game.lock(|game| game.handle_input(input));
+                    if outcome.redraw {
+                        cx.shared.next_render.lock(|handle| {
+                            if let Some(next_render) = handle.take() {
+                                *handle = next_render.reschedule_after(0.millis()).ok();
+                            }
+                        });
                     }
                 }
             }
@@ -278,37 +289,40 @@ mod app {
         scan::spawn_after(10.millis()).ok();
     }
 
-    #[task(priority = 2)]
-    fn play_note_with_delay(_cx: play_note_with_delay::Context, index: usize) {
-        if index < NOTES.len() {
-            let note = &NOTES[index];
-            if note.delay > 0 {
-                start_note::spawn_after(note.delay.millis(), index).ok();
-            } else {
-                start_note::spawn(index).ok();
-            }
-        }
+    #[task(priority = 2, shared = [sequencer])]
+    fn start_sound(mut cx: start_sound::Context, event: SoundEvent) {
+        let now = monotonic_millis();
+        cx.shared
+            .sequencer
+            .lock(|sequencer| sequencer.play(event.tune(), now));
+        sound::spawn().ok();
     }
 
-    #[task(priority = 2, shared = [pwm])]
-    fn start_note(mut cx: start_note::Context, index: usize) {
-        if index < NOTES.len() {
-            let note = &NOTES[index];
+    /// Applies the tone actions due and comes back for the next one.
+    #[task(priority = 2, capacity = 2, shared = [pwm, sequencer, next_sound])]
+    fn sound(mut cx: sound::Context) {
+        let now = monotonic_millis();
+        let next_due = cx.shared.sequencer.lock(|sequencer| {
             cx.shared.pwm.lock(|pwm| {
-                pwm.set_period((note.frequency as u32).hz());
-                let max_duty = pwm.get_max_duty();
-                pwm.set_duty(Channel::_0, max_duty / 2);
-                pwm.enable(Channel::_0);
+                while let Some(action) = sequencer.update(now) {
+                    match action {
+                        ToneAction::On { frequency } => {
+                            pwm.set_period((frequency as u32).hz());
+                            let max_duty = pwm.get_max_duty();
+                            pwm.set_duty(Channel::_0, max_duty / 2);
+                            pwm.enable(Channel::_0);
+                        }
+                        ToneAction::Off => pwm.disable(Channel::_0),
+                    }
+                }
             });
-            end_note::spawn_after((note.duration as u64).millis(), index).ok();
-        }
-    }
-
-    #[task(priority = 2, shared = [pwm])]
-    fn end_note(mut cx: end_note::Context, index: usize) {
-        cx.shared.pwm.lock(|pwm| {
-            pwm.disable(Channel::_0);
+            sequencer.next_due()
+        });
+        cx.shared.next_sound.lock(|handle| {
+            if let Some(next_sound) = handle.take() {
+                next_sound.cancel().ok();
+            }
+            *handle = next_due.and_then(|due| sound::spawn_after((due - now).millis()).ok());
         });
-        play_note_with_delay::spawn_after(10.millis(), index + 1).ok();
     }
 }
@@ -0,0 +1,480 @@
+use crate::maze::{CellList, CellMap, Coord, Direction, Maze, MazeError};
+use heapless::Vec;
+use rand::{prelude::*, Rng};
+
+/// A strategy for carving a perfect maze out of a maze with no doors.
+pub trait MazeAlgorithm {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError>;
+}
+
+/// All of the algorithms in this module, for choosing one at run time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    #[default]
+    GrowingTree,
+    RecursiveBacktracker,
+    Kruskal,
+    Prim,
+    Wilson,
+    AldousBroder,
+    Eller,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 7] = [
+        Algorithm::GrowingTree,
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Kruskal,
+        Algorithm::Prim,
+        Algorithm::Wilson,
+        Algorithm::AldousBroder,
+        Algorithm::Eller,
+    ];
+}
+
+impl From<Algorithm> for &'static str {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::GrowingTree => "Growing Tree",
+            Algorithm::RecursiveBacktracker => "Backtracker",
+            Algorithm::Kruskal => "Kruskal",
+            Algorithm::Prim => "Prim",
+            Algorithm::Wilson => "Wilson",
+            Algorithm::AldousBroder => "Aldous-Broder",
+            Algorithm::Eller => "Eller",
+        }
+    }
+}
+
+impl MazeAlgorithm for Algorithm {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        match self {
+            Self::GrowingTree => GrowingTree.carve(maze, rng),
+            Self::RecursiveBacktracker => RecursiveBacktracker.carve(maze, rng),
+            Self::Kruskal => Kruskal.carve(maze, rng),
+            Self::Prim => Prim.carve(maze, rng),
+            Self::Wilson => Wilson.carve(maze, rng),
+            Self::AldousBroder => AldousBroder.carve(maze, rng),
+            Self::Eller => Eller.carve(maze, rng),
+        }
+    }
+}
+
+fn neighbors<const X: usize, const Y: usize, const Z: usize>(
+    coord: Coord,
+) -> Vec<(Direction, Coord), 6> {
+    Direction::ALL
+        .iter()
+        .filter_map(|direction| {
+            Maze::<X, Y, Z>::neighbor(coord, *direction).map(|next| (*direction, next))
+        })
+        .collect()
+}
+
+fn random_neighbor<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+    coord: Coord,
+    rng: &mut R,
+) -> (Direction, Coord) {
+    *neighbors::<X, Y, Z>(coord)
+        .choose(rng)
+        .expect("every room has a neighbor")
+}
+
+/// Growing tree that picks a random cell from the frontier, which makes
+/// mazes with lots of short branches. This is the original Quinti-Maze
+/// generator.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GrowingTree;
+
+impl MazeAlgorithm for GrowingTree {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut cells = CellList::<Coord, X, Y, Z>::new();
+        let mut visited = CellMap::<bool, X, Y, Z>::new(false);
+        let mut directions = Direction::ALL;
+
+        cells.push(Maze::<X, Y, Z>::random_coord(rng))?;
+
+        while !cells.is_empty() {
+            let mut index = Some(rng.gen_range(0..cells.len()));
+            let coords = cells[index.unwrap_or(0)];
+
+            directions.shuffle(rng);
+            for dir in &directions {
+                let next = match Maze::<X, Y, Z>::neighbor(coords, *dir) {
+                    Some(next) => next,
+                    None => continue,
+                };
+
+                if visited[&next] {
+                    continue;
+                }
+
+                if let Some(next) = maze.carve_passage(coords, *dir) {
+                    visited[&coords] = true;
+                    visited[&next] = true;
+                    cells.push(next)?;
+                    index = None;
+                    break;
+                }
+            }
+
+            if let Some(index) = index {
+                cells.remove(index);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Depth first search that always extends the newest cell, which makes long
+/// winding corridors with few dead ends.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RecursiveBacktracker;
+
+impl MazeAlgorithm for RecursiveBacktracker {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut stack = CellList::<Coord, X, Y, Z>::new();
+        let mut visited = CellMap::<bool, X, Y, Z>::new(false);
+
+        let start = Maze::<X, Y, Z>::random_coord(rng);
+        visited[&start] = true;
+        stack.push(start)?;
+
+        while let Some(coord) = stack.last().copied() {
+            let unvisited: Vec<(Direction, Coord), 6> = neighbors::<X, Y, Z>(coord)
+                .into_iter()
+                .filter(|(_, next)| !visited[next])
+                .collect();
+            match unvisited.choose(rng) {
+                Some((direction, next)) => {
+                    maze.carve_passage(coord, *direction);
+                    visited[next] = true;
+                    stack.push(*next)?;
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Randomized Kruskal, joining sets of cells across walls taken in random
+/// order. Makes lots of short dead ends, much like Prim.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Kruskal;
+
+impl Kruskal {
+    const DIRECTIONS: [Direction; 3] = [Direction::East, Direction::South, Direction::Up];
+
+    fn find_set(sets: &mut [usize], mut index: usize) -> usize {
+        while sets[index] != index {
+            sets[index] = sets[sets[index]];
+            index = sets[index];
+        }
+        index
+    }
+}
+
+impl MazeAlgorithm for Kruskal {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        // Every cell owns the walls on its east, south and up sides.
+        let mut walls = CellMap::<[usize; 3], X, Y, Z>::new([0; 3]);
+        let walls = walls.as_mut_slice().as_flattened_mut();
+        for (index, wall) in walls.iter_mut().enumerate() {
+            *wall = index;
+        }
+        walls.shuffle(rng);
+
+        let mut sets = CellMap::<usize, X, Y, Z>::new(0);
+        let sets = sets.as_mut_slice();
+        for (index, set) in sets.iter_mut().enumerate() {
+            *set = index;
+        }
+
+        for wall in walls.iter() {
+            let coord = Maze::<X, Y, Z>::coord_at(wall / 3);
+            let direction = Self::DIRECTIONS[wall % 3];
+            let next = match Maze::<X, Y, Z>::neighbor(coord, direction) {
+                Some(next) => next,
+                None => continue,
+            };
+            let set = Self::find_set(sets, Maze::<X, Y, Z>::index_of(&coord));
+            let next_set = Self::find_set(sets, Maze::<X, Y, Z>::index_of(&next));
+            if set != next_set {
+                sets[next_set] = set;
+                maze.carve_passage(coord, direction);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Randomized Prim, growing the maze from a random frontier cell each step.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Prim;
+
+impl Prim {
+    fn add_frontier<const X: usize, const Y: usize, const Z: usize>(
+        coord: Coord,
+        state: &mut CellMap<PrimState, X, Y, Z>,
+        frontier: &mut CellList<Coord, X, Y, Z>,
+    ) -> Result<(), MazeError> {
+        state[&coord] = PrimState::InMaze;
+        for (_, next) in neighbors::<X, Y, Z>(coord) {
+            if state[&next] == PrimState::Outside {
+                state[&next] = PrimState::Frontier;
+                frontier.push(next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrimState {
+    Outside,
+    Frontier,
+    InMaze,
+}
+
+impl MazeAlgorithm for Prim {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut state = CellMap::<PrimState, X, Y, Z>::new(PrimState::Outside);
+        let mut frontier = CellList::<Coord, X, Y, Z>::new();
+
+        Self::add_frontier(
+            Maze::<X, Y, Z>::random_coord(rng),
+            &mut state,
+            &mut frontier,
+        )?;
+
+        while !frontier.is_empty() {
+            let coord = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            let in_maze: Vec<(Direction, Coord), 6> = neighbors::<X, Y, Z>(coord)
+                .into_iter()
+                .filter(|(_, next)| state[next] == PrimState::InMaze)
+                .collect();
+            if let Some((direction, _)) = in_maze.choose(rng) {
+                maze.carve_passage(coord, *direction);
+            }
+            Self::add_frontier(coord, &mut state, &mut frontier)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wilson's loop-erased random walks, which pick uniformly from every
+/// possible spanning tree.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Wilson;
+
+impl MazeAlgorithm for Wilson {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut in_tree = CellMap::<bool, X, Y, Z>::new(false);
+        let mut walk = CellMap::<Direction, X, Y, Z>::new(Direction::default());
+
+        in_tree[&Maze::<X, Y, Z>::random_coord(rng)] = true;
+
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            let start = Maze::<X, Y, Z>::coord_at(index);
+
+            // Walk until hitting the tree, remembering only the last way out
+            // of each cell so that loops erase themselves.
+            let mut coord = start;
+            while !in_tree[&coord] {
+                let (direction, next) = random_neighbor::<R, X, Y, Z>(coord, rng);
+                walk[&coord] = direction;
+                coord = next;
+            }
+
+            let mut coord = start;
+            while !in_tree[&coord] {
+                in_tree[&coord] = true;
+                coord = maze
+                    .carve_passage(coord, walk[&coord])
+                    .ok_or(MazeError::OutOfBounds(coord))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Aldous-Broder random walk, which carves into every unvisited cell it
+/// stumbles into. Uniform like Wilson but slower to finish.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AldousBroder;
+
+impl MazeAlgorithm for AldousBroder {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut visited = CellMap::<bool, X, Y, Z>::new(false);
+        let mut remaining = Maze::<X, Y, Z>::cell_count() - 1;
+
+        let mut coord = Maze::<X, Y, Z>::random_coord(rng);
+        visited[&coord] = true;
+
+        while remaining > 0 {
+            let (direction, next) = random_neighbor::<R, X, Y, Z>(coord, rng);
+            if !visited[&next] {
+                maze.carve_passage(coord, direction);
+                visited[&next] = true;
+                remaining -= 1;
+            }
+            coord = next;
+        }
+        Ok(())
+    }
+}
+
+/// Eller's algorithm, extended to three dimensions by treating each floor as
+/// a row. Only one floor of set labels is kept at a time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Eller;
+
+impl Eller {
+    fn relabel<const X: usize, const Y: usize>(
+        labels: &mut [[usize; X]; Y],
+        from: usize,
+        to: usize,
+    ) {
+        for label in labels.as_flattened_mut() {
+            if *label == from {
+                *label = to;
+            }
+        }
+    }
+}
+
+impl MazeAlgorithm for Eller {
+    fn carve<R: Rng, const X: usize, const Y: usize, const Z: usize>(
+        &self,
+        maze: &mut Maze<X, Y, Z>,
+        rng: &mut R,
+    ) -> Result<(), MazeError> {
+        let mut labels = [[0usize; X]; Y];
+        let mut next_label = 1;
+
+        for z in 0..Z {
+            let last_floor = z + 1 == Z;
+
+            for label in labels.as_flattened_mut() {
+                if *label == 0 {
+                    *label = next_label;
+                    next_label += 1;
+                }
+            }
+
+            // Join neighbouring sets on this floor. The top floor has to join
+            // all of them, as nothing above can connect them later.
+            for y in 0..Y {
+                for x in 0..X {
+                    let coord = Coord {
+                        x: x as isize,
+                        y: y as isize,
+                        z: z as isize,
+                    };
+                    for direction in [Direction::East, Direction::South] {
+                        let next = match Maze::<X, Y, Z>::neighbor(coord, direction) {
+                            Some(next) => next,
+                            None => continue,
+                        };
+                        let label = labels[y][x];
+                        let neighbor_label = labels[next.y as usize][next.x as usize];
+                        if label != neighbor_label && (last_floor || rng.gen_bool(0.5)) {
+                            maze.carve_passage(coord, direction);
+                            Self::relabel(&mut labels, neighbor_label, label);
+                        }
+                    }
+                }
+            }
+
+            if last_floor {
+                break;
+            }
+
+            // Every set needs at least one way up to carry it to the next
+            // floor.
+            let mut order = [[Coord::default(); X]; Y];
+            for (index, coord) in order.as_flattened_mut().iter_mut().enumerate() {
+                *coord = Maze::<X, Y, Z>::coord_at(index + z * X * Y);
+            }
+            order.as_flattened_mut().shuffle(rng);
+
+            let mut next_labels = [[0usize; X]; Y];
+            for coord in order.as_flattened() {
+                let (x, y) = (coord.x as usize, coord.y as usize);
+                let label = labels[y][x];
+                let has_way_up = next_labels.as_flattened().contains(&label);
+                if !has_way_up || rng.gen_bool(0.3) {
+                    maze.carve_passage(*coord, Direction::Up);
+                    next_labels[y][x] = label;
+                }
+            }
+            labels = next_labels;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::maze::{find_path_to_exit, MazeGenerator};
+
+    fn assert_perfect<const X: usize, const Y: usize, const Z: usize>(maze: &Maze<X, Y, Z>) {
+        maze.check(true, |violation| panic!("{:?}", violation));
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            let coord = Maze::<X, Y, Z>::coord_at(index);
+            let (found, _) = find_path_to_exit(maze, coord);
+            assert!(found, "no way out of {:?}", coord);
+        }
+    }
+
+    #[test]
+    fn test_algorithms() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..4 {
+                let mut generator = MazeGenerator::<5, 5, 5>::default();
+                generator.generate_with(&algorithm, Some(seed));
+                assert_perfect(&generator.take());
+
+                let mut generator = MazeGenerator::<4, 3, 2>::default();
+                generator.generate_with(&algorithm, Some(seed));
+                assert_perfect(&generator.take());
+            }
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use crate::maze::{Coord, Direction};
+use crate::{
+    maze::{Coord, Direction, Maze, SolutionPath},
+    palette::{Fill, Palette},
+    score::{GameResult, HighScore, HighScores, RoomSet, Score},
+};
 use core::fmt;
 use embedded_graphics::{
-    mono_font::{ascii::FONT_8X13_BOLD, MonoTextStyle, MonoTextStyleBuilder},
-    pixelcolor::Rgb565,
+    mono_font::{
+        ascii::{FONT_10X20, FONT_8X13_BOLD},
+        MonoTextStyle, MonoTextStyleBuilder,
+    },
     prelude::*,
-    primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle},
+    primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, Triangle},
     text::{Alignment, Text},
 };
 use heapless::String;
@@ -21,20 +27,15 @@ macro_rules! map_y_to_ratio {
     };
 }
 
-macro_rules! map_x_to_screen {
-    ($value:expr) => {
-        (SCREEN_SIZE.width as f32 * $value + 0.9) as i32
-    };
-}
+pub const ORIGINAL_SCREEN_SIZE: Size = Size::new(280, 192);
 
-macro_rules! map_y_to_screen {
+/// Rows of the screens that aren't in the original, as laid out on the LCD.
+macro_rules! lcd_y_to_ratio {
     ($value:expr) => {
-        (SCREEN_SIZE.height as f32 * $value + 0.9) as i32
+        $value / Viewport::LCD.size.height as f32
     };
 }
 
-pub const ORIGINAL_SCREEN_SIZE: Size = Size::new(280, 192);
-
 const ORIGINAL_FRONT_LEFT: f32 = 0.0;
 const ORIGINAL_FRONT_TOP: f32 = 0.0;
 const ORIGINAL_FRONT_RIGHT: f32 = map_x_to_ratio!(279.0);
@@ -74,83 +75,194 @@ const ORIGINAL_FD_FRONT_BOTTOM: f32 = map_y_to_ratio!(129.0);
 const ORIGINAL_FD_BACK_TOP: f32 = map_y_to_ratio!(69.0);
 const ORIGINAL_FD_BACK_BOTTOM: f32 = map_y_to_ratio!(119.0);
 
-#[cfg(feature = "lcd_screen")]
-pub const SCREEN_SIZE: Size = Size::new(320, 240);
-#[cfg(feature = "memory_screen")]
-pub const SCREEN_SIZE: Size = Size::new(400, 240);
-
-const FRONT_LEFT: i32 = map_x_to_screen!(ORIGINAL_FRONT_LEFT);
-const FRONT_RIGHT: i32 = map_x_to_screen!(ORIGINAL_FRONT_RIGHT);
-const FRONT_TOP: i32 = map_y_to_screen!(ORIGINAL_FRONT_TOP);
-const FRONT_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_FRONT_BOTTOM);
-const BACK_LEFT: i32 = map_x_to_screen!(ORIGINAL_BACK_LEFT);
-const BACK_RIGHT: i32 = map_x_to_screen!(ORIGINAL_BACK_RIGHT);
-const BACK_TOP: i32 = map_y_to_screen!(ORIGINAL_BACK_TOP);
-const BACK_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_BACK_BOTTOM);
-
-const LD_LEFT: i32 = map_x_to_screen!(ORIGINAL_LD_LEFT);
-const LD_RIGHT: i32 = map_x_to_screen!(ORIGINAL_LD_RIGHT);
-const LRD_FRONT_TOP: i32 = map_y_to_screen!(ORIGINAL_LRD_FRONT_TOP);
-const LRD_BACK_TOP: i32 = map_y_to_screen!(ORIGINAL_LRD_BACK_TOP);
-const LRD_FRONT_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_LRD_FRONT_BOTTOM);
-const LRD_BACK_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_LRD_BACK_BOTTOM);
-
-const RD_LEFT: i32 = map_x_to_screen!(ORIGINAL_RD_LEFT);
-const RD_RIGHT: i32 = map_x_to_screen!(ORIGINAL_RD_RIGHT);
-
-const TD_TOP: i32 = map_y_to_screen!(ORIGINAL_TD_TOP);
-const TD_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_TD_BOTTOM);
-const TBD_FRONT_LEFT: i32 = map_x_to_screen!(ORIGINAL_TBD_FRONT_LEFT);
-const TBD_BACK_LEFT: i32 = map_x_to_screen!(ORIGINAL_TBD_BACK_LEFT);
-const TBD_FRONT_RIGHT: i32 = map_x_to_screen!(ORIGINAL_TBD_FRONT_RIGHT);
-const TBD_BACK_RIGHT: i32 = map_x_to_screen!(ORIGINAL_TBD_BACK_RIGHT);
-
-const BD_TOP: i32 = map_y_to_screen!(ORIGINAL_BD_TOP);
-const BD_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_BD_BOTTOM);
-
-const FD_FRONT_LEFT: i32 = map_x_to_screen!(ORIGINAL_FD_FRONT_LEFT);
-const FD_BACK_LEFT: i32 = map_x_to_screen!(ORIGINAL_FD_BACK_LEFT);
-const FD_FRONT_RIGHT: i32 = map_x_to_screen!(ORIGINAL_FD_FRONT_RIGHT);
-const FD_BACK_RIGHT: i32 = map_x_to_screen!(ORIGINAL_FD_BACK_RIGHT);
-const FD_FRONT_TOP: i32 = map_y_to_screen!(ORIGINAL_FD_FRONT_TOP);
-const FD_FRONT_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_FD_FRONT_BOTTOM);
-const FD_BACK_TOP: i32 = map_y_to_screen!(ORIGINAL_FD_BACK_TOP);
-const FD_BACK_BOTTOM: i32 = map_y_to_screen!(ORIGINAL_FD_BACK_BOTTOM);
-
-fn color_for_door(showing: bool) -> Rgb565 {
+/// The part of a display the game is drawn in. The room keeps the
+/// proportions of the original screen, stretched to fit whatever size this is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub top_left: Point,
+    pub size: Size,
+}
+
+impl Viewport {
+    /// The whole of the device's 320x240 LCD.
+    pub const LCD: Self = Self::new(Point::zero(), Size::new(320, 240));
+    /// The whole of a 400x240 memory display.
+    pub const MEMORY_DISPLAY: Self = Self::new(Point::zero(), Size::new(400, 240));
+
+    pub const fn new(top_left: Point, size: Size) -> Self {
+        Self { top_left, size }
+    }
+
+    pub fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(self.top_left, self.size)
+    }
+
+    /// Fills the viewport, leaving the rest of the display alone.
+    pub fn clear<D>(&self, display: &mut D, color: D::Color) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: Palette,
+    {
+        display.fill_solid(&self.bounding_box(), color)
+    }
+
+    /// The column `ratio` of the way across, as measured on the original
+    /// screen. Rounding up can't take it past the last column, which it
+    /// would on narrow viewports.
+    fn x(&self, ratio: f32) -> i32 {
+        let x = (self.size.width as f32 * ratio + 0.9) as i32;
+        self.top_left.x + x.min(self.size.width as i32 - 1)
+    }
+
+    /// The row `ratio` of the way down, as measured on the original screen.
+    fn y(&self, ratio: f32) -> i32 {
+        let y = (self.size.height as f32 * ratio + 0.9) as i32;
+        self.top_left.y + y.min(self.size.height as i32 - 1)
+    }
+
+    fn point(&self, x_ratio: f32, y_ratio: f32) -> Point {
+        Point::new(self.x(x_ratio), self.y(y_ratio))
+    }
+
+    fn center_x(&self) -> i32 {
+        self.top_left.x + (self.size.width / 2) as i32
+    }
+
+    /// The row `offset` pixels down from the top.
+    fn top(&self, offset: i32) -> i32 {
+        self.top_left.y + offset
+    }
+
+    /// The row `offset` pixels up from the bottom.
+    fn bottom(&self, offset: i32) -> i32 {
+        self.top_left.y + self.size.height as i32 - offset
+    }
+
+    /// The column `offset` pixels in from the left.
+    fn left(&self, offset: i32) -> i32 {
+        self.top_left.x + offset
+    }
+
+    /// The column `offset` pixels in from the right.
+    fn right(&self, offset: i32) -> i32 {
+        self.top_left.x + self.size.width as i32 - offset
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::LCD
+    }
+}
+
+fn color_for_door<C: Palette>(showing: bool) -> C {
     if showing {
-        Rgb565::BLACK
+        C::DOOR
     } else {
-        Rgb565::WHITE
+        C::NO_DOOR
     }
 }
 
-fn draw_lines<D>(points: &[Point], display: &mut D) -> Result<(), D::Error>
-where
-    D: DrawTarget<Color = Rgb565>,
-{
-    let line_style = PrimitiveStyle::with_stroke(Rgb565::BLACK, 1);
-    let mut last_point = None;
+/// Where a room is drawn in the viewport: the room the player is in, or one
+/// further along seen through front doors, scaled toward the middle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RoomView {
+    scale_x: f32,
+    scale_y: f32,
+    offset_x: f32,
+    offset_y: f32,
+}
 
-    for point in points {
-        if let Some(last_point) = last_point.as_ref() {
-            Line::new(*last_point, *point)
+impl RoomView {
+    const CURRENT: Self = Self {
+        scale_x: 1.0,
+        scale_y: 1.0,
+        offset_x: 0.0,
+        offset_y: 0.0,
+    };
+
+    fn point(&self, point: Point) -> Point {
+        Point::new(
+            (point.x as f32 * self.scale_x + self.offset_x + 0.5) as i32,
+            (point.y as f32 * self.scale_y + self.offset_y + 0.5) as i32,
+        )
+    }
+
+    /// This view of `inner`, a view relative to this room.
+    fn within(&self, inner: &Self) -> Self {
+        Self {
+            scale_x: self.scale_x * inner.scale_x,
+            scale_y: self.scale_y * inner.scale_y,
+            offset_x: self.offset_x + inner.offset_x * self.scale_x,
+            offset_y: self.offset_y + inner.offset_y * self.scale_y,
+        }
+    }
+
+    /// The view of the room through this room's front door. A room's depth
+    /// takes its front down to its back wall, and the doorway is as deep
+    /// again, so the next room starts two room depths further in, standing
+    /// on the floor at the far end of the doorway.
+    fn next(&self, viewport: &Viewport) -> Self {
+        let front_left = viewport.x(ORIGINAL_FRONT_LEFT) as f32;
+        let front_top = viewport.y(ORIGINAL_FRONT_TOP) as f32;
+        let front_right = viewport.x(ORIGINAL_FRONT_RIGHT) as f32;
+        let front_bottom = viewport.y(ORIGINAL_FRONT_BOTTOM) as f32;
+        let back_left = viewport.x(ORIGINAL_BACK_LEFT) as f32;
+        let back_top = viewport.y(ORIGINAL_BACK_TOP) as f32;
+        let scale_x =
+            (viewport.x(ORIGINAL_BACK_RIGHT) as f32 - back_left) / (front_right - front_left);
+        let scale_y =
+            (viewport.y(ORIGINAL_BACK_BOTTOM) as f32 - back_top) / (front_bottom - front_top);
+        let deeper = Self {
+            scale_x,
+            scale_y,
+            offset_x: back_left - front_left * scale_x,
+            offset_y: back_top - front_top * scale_y,
+        };
+        let mut next = deeper.within(&deeper);
+        next.offset_y = viewport.y(ORIGINAL_FD_BACK_BOTTOM) as f32 - front_bottom * next.scale_y;
+        self.within(&next)
+    }
+
+    /// The far end of the front doorway, which is all that can be seen of
+    /// the rooms beyond it.
+    fn doorway(&self, viewport: &Viewport) -> Rectangle {
+        let top_left = viewport.point(ORIGINAL_FD_BACK_LEFT, ORIGINAL_FD_BACK_TOP);
+        let bottom_right = viewport.point(ORIGINAL_FD_BACK_RIGHT, ORIGINAL_FD_BACK_BOTTOM);
+        Rectangle::with_corners(
+            self.point(top_left + Point::new(1, 1)),
+            self.point(bottom_right - Point::new(1, 1)),
+        )
+    }
+
+    fn draw_lines<D>(
+        &self,
+        points: &[Point],
+        color: D::Color,
+        display: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: Palette,
+    {
+        let line_style = PrimitiveStyle::with_stroke(color, 1);
+        for pair in points.windows(2) {
+            Line::new(self.point(pair[0]), self.point(pair[1]))
                 .into_styled(line_style)
                 .draw(display)?;
         }
-        last_point = Some(*point);
+        Ok(())
     }
-
-    Ok(())
 }
 
 fn draw_lines_with_color<D>(
     points: &[Point],
-    color: Rgb565,
+    color: D::Color,
     display: &mut D,
 ) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
     let line_style = PrimitiveStyle::with_stroke(color, 1);
     let mut last_point = None;
@@ -167,254 +279,382 @@ where
     Ok(())
 }
 
-pub fn draw_room<D>(display: &mut D) -> Result<(), D::Error>
+pub fn draw_room<D>(display: &mut D, viewport: &Viewport) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    const TOP_LEFT: Point = Point::new(FRONT_LEFT, FRONT_TOP);
-    const TOP_RIGHT: Point = Point::new(FRONT_RIGHT, FRONT_TOP);
-    const BOTTOM_LEFT: Point = Point::new(FRONT_LEFT, FRONT_BOTTOM);
-    const BOTTOM_RIGHT: Point = Point::new(FRONT_RIGHT, FRONT_BOTTOM);
-
-    const ROOM_OUTLINE: [Point; 10] = [
-        TOP_LEFT,
-        TOP_RIGHT,
-        BOTTOM_RIGHT,
-        BOTTOM_LEFT,
-        TOP_LEFT,
-        Point::new(BACK_LEFT, BACK_TOP),
-        Point::new(BACK_RIGHT, BACK_TOP),
-        Point::new(BACK_RIGHT, BACK_BOTTOM),
-        Point::new(BACK_LEFT, BACK_BOTTOM),
-        Point::new(BACK_LEFT, BACK_TOP),
-    ];
-
-    draw_lines(&ROOM_OUTLINE, display)?;
-
-    const BOTTOM_RIGHT_LINE: [Point; 2] = [Point::new(BACK_RIGHT, BACK_TOP), TOP_RIGHT];
-    draw_lines(&BOTTOM_RIGHT_LINE, display)?;
-
-    const TOP_RIGHT_LINE: [Point; 2] = [Point::new(BACK_RIGHT, BACK_BOTTOM), BOTTOM_RIGHT];
-    draw_lines(&TOP_RIGHT_LINE, display)?;
-
-    const BOTTOM_LEFT_LINE: [Point; 2] = [Point::new(BACK_LEFT, BACK_BOTTOM), BOTTOM_LEFT];
-    draw_lines(&BOTTOM_LEFT_LINE, display)
+    draw_room_in(display, viewport, &RoomView::CURRENT)
 }
 
-pub fn draw_left_door<D>(display: &mut D, showing: bool) -> Result<(), D::Error>
+fn draw_room_in<D>(display: &mut D, viewport: &Viewport, view: &RoomView) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let color = color_for_door(showing);
-
-    const LD_FRAME: [Point; 4] = [
-        Point::new(LD_LEFT, LRD_FRONT_BOTTOM),
-        Point::new(LD_LEFT, LRD_FRONT_TOP),
-        Point::new(LD_RIGHT, LRD_BACK_TOP),
-        Point::new(LD_RIGHT, LRD_BACK_BOTTOM),
+    let top_left = viewport.point(ORIGINAL_FRONT_LEFT, ORIGINAL_FRONT_TOP);
+    let top_right = viewport.point(ORIGINAL_FRONT_RIGHT, ORIGINAL_FRONT_TOP);
+    let bottom_left = viewport.point(ORIGINAL_FRONT_LEFT, ORIGINAL_FRONT_BOTTOM);
+    let bottom_right = viewport.point(ORIGINAL_FRONT_RIGHT, ORIGINAL_FRONT_BOTTOM);
+    let back_top_left = viewport.point(ORIGINAL_BACK_LEFT, ORIGINAL_BACK_TOP);
+    let back_top_right = viewport.point(ORIGINAL_BACK_RIGHT, ORIGINAL_BACK_TOP);
+    let back_bottom_left = viewport.point(ORIGINAL_BACK_LEFT, ORIGINAL_BACK_BOTTOM);
+    let back_bottom_right = viewport.point(ORIGINAL_BACK_RIGHT, ORIGINAL_BACK_BOTTOM);
+
+    let room_outline = [
+        top_left,
+        top_right,
+        bottom_right,
+        bottom_left,
+        top_left,
+        back_top_left,
+        back_top_right,
+        back_bottom_right,
+        back_bottom_left,
+        back_top_left,
     ];
-    draw_lines_with_color(&LD_FRAME, color, display)?;
+    view.draw_lines(&room_outline, D::Color::WALL, display)?;
 
-    const LD_TOP: [Point; 2] = [
-        Point::new(LD_LEFT, LRD_BACK_TOP),
-        Point::new(LD_RIGHT, LRD_BACK_TOP),
-    ];
-    draw_lines_with_color(&LD_TOP, color, display)?;
-    const LD_BOTTOM: [Point; 2] = [
-        Point::new(LD_LEFT, LRD_BACK_BOTTOM),
-        Point::new(LD_RIGHT, LRD_BACK_BOTTOM),
-    ];
-    draw_lines_with_color(&LD_BOTTOM, color, display)
+    view.draw_lines(&[back_top_right, top_right], D::Color::WALL, display)?;
+    view.draw_lines(&[back_bottom_right, bottom_right], D::Color::WALL, display)?;
+    view.draw_lines(&[back_bottom_left, bottom_left], D::Color::WALL, display)
 }
 
-pub fn draw_right_door<D>(display: &mut D, showing: bool) -> Result<(), D::Error>
+pub fn draw_left_door<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    showing: bool,
+) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let color = color_for_door(showing);
-
-    const RD_FRAME: [Point; 4] = [
-        Point::new(RD_LEFT, LRD_FRONT_BOTTOM),
-        Point::new(RD_LEFT, LRD_FRONT_TOP),
-        Point::new(RD_RIGHT, LRD_BACK_TOP),
-        Point::new(RD_RIGHT, LRD_BACK_BOTTOM),
-    ];
-    draw_lines_with_color(&RD_FRAME, color, display)?;
+    draw_side_door_in(
+        display,
+        viewport,
+        &RoomView::CURRENT,
+        (ORIGINAL_LD_LEFT, ORIGINAL_LD_RIGHT),
+        color_for_door(showing),
+    )
+}
 
-    const RD_TOP: [Point; 2] = [
-        Point::new(RD_LEFT, LRD_BACK_TOP),
-        Point::new(RD_RIGHT, LRD_BACK_TOP),
-    ];
-    draw_lines_with_color(&RD_TOP, color, display)?;
-    const RD_BOTTOM: [Point; 2] = [
-        Point::new(RD_LEFT, LRD_BACK_BOTTOM),
-        Point::new(RD_RIGHT, LRD_BACK_BOTTOM),
-    ];
-    draw_lines_with_color(&RD_BOTTOM, color, display)
+pub fn draw_right_door<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    showing: bool,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_side_door_in(
+        display,
+        viewport,
+        &RoomView::CURRENT,
+        (ORIGINAL_RD_LEFT, ORIGINAL_RD_RIGHT),
+        color_for_door(showing),
+    )
 }
 
-pub fn draw_top_door<D>(display: &mut D, showing: bool) -> Result<(), D::Error>
+/// Draws a door in the left or right wall, from its edge nearer the front of
+/// the room to the one nearer the back.
+fn draw_side_door_in<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    view: &RoomView,
+    (front, back): (f32, f32),
+    color: D::Color,
+) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let color = color_for_door(showing);
+    let front_bottom = viewport.point(front, ORIGINAL_LRD_FRONT_BOTTOM);
+    let front_top = viewport.point(front, ORIGINAL_LRD_FRONT_TOP);
+    let back_top = viewport.point(back, ORIGINAL_LRD_BACK_TOP);
+    let back_bottom = viewport.point(back, ORIGINAL_LRD_BACK_BOTTOM);
+    view.draw_lines(
+        &[front_bottom, front_top, back_top, back_bottom],
+        color,
+        display,
+    )?;
+
+    let front = viewport.x(front);
+    view.draw_lines(&[Point::new(front, back_top.y), back_top], color, display)?;
+    view.draw_lines(
+        &[Point::new(front, back_bottom.y), back_bottom],
+        color,
+        display,
+    )
+}
 
-    const TD_FRAME: [Point; 5] = [
-        Point::new(TBD_FRONT_LEFT, TD_TOP),
-        Point::new(TBD_FRONT_RIGHT, TD_TOP),
-        Point::new(TBD_BACK_RIGHT, TD_BOTTOM),
-        Point::new(TBD_BACK_LEFT, TD_BOTTOM),
-        Point::new(TBD_FRONT_LEFT, TD_TOP),
-    ];
-    draw_lines_with_color(&TD_FRAME, color, display)?;
-    const TD_LEFT: [Point; 2] = [
-        Point::new(TBD_BACK_LEFT, TD_TOP),
-        Point::new(TBD_BACK_LEFT, TD_BOTTOM),
-    ];
-    draw_lines_with_color(&TD_LEFT, color, display)?;
-    const TD_RIGHT: [Point; 2] = [
-        Point::new(TBD_BACK_RIGHT, TD_TOP),
-        Point::new(TBD_BACK_RIGHT, TD_BOTTOM),
-    ];
-    draw_lines_with_color(&TD_RIGHT, color, display)
+pub fn draw_top_door<D>(display: &mut D, viewport: &Viewport, showing: bool) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_floor_door_in(
+        display,
+        viewport,
+        &RoomView::CURRENT,
+        (ORIGINAL_TD_TOP, ORIGINAL_TD_BOTTOM),
+        color_for_door(showing),
+    )
 }
 
-pub fn draw_bottom_door<D>(display: &mut D, showing: bool) -> Result<(), D::Error>
+pub fn draw_bottom_door<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    showing: bool,
+) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let color = color_for_door(showing);
+    draw_floor_door_in(
+        display,
+        viewport,
+        &RoomView::CURRENT,
+        (ORIGINAL_BD_TOP, ORIGINAL_BD_BOTTOM),
+        color_for_door(showing),
+    )
+}
 
-    const BD_FRAME: [Point; 5] = [
-        Point::new(TBD_FRONT_LEFT, BD_TOP),
-        Point::new(TBD_FRONT_RIGHT, BD_TOP),
-        Point::new(TBD_BACK_RIGHT, BD_BOTTOM),
-        Point::new(TBD_BACK_LEFT, BD_BOTTOM),
-        Point::new(TBD_FRONT_LEFT, BD_TOP),
-    ];
-    draw_lines_with_color(&BD_FRAME, color, display)?;
-    const BD_LEFT: [Point; 2] = [
-        Point::new(TBD_BACK_LEFT, BD_TOP),
-        Point::new(TBD_BACK_LEFT, BD_BOTTOM),
-    ];
-    draw_lines_with_color(&BD_LEFT, color, display)?;
-    const BD_RIGHT: [Point; 2] = [
-        Point::new(TBD_BACK_RIGHT, BD_TOP),
-        Point::new(TBD_BACK_RIGHT, BD_BOTTOM),
-    ];
-    draw_lines_with_color(&BD_RIGHT, color, display)
+/// Draws a door in the ceiling or floor, from its edge nearer the front of
+/// the room to the one nearer the back.
+fn draw_floor_door_in<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    view: &RoomView,
+    (front, back): (f32, f32),
+    color: D::Color,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let front_left = viewport.point(ORIGINAL_TBD_FRONT_LEFT, front);
+    let front_right = viewport.point(ORIGINAL_TBD_FRONT_RIGHT, front);
+    let back_right = viewport.point(ORIGINAL_TBD_BACK_RIGHT, back);
+    let back_left = viewport.point(ORIGINAL_TBD_BACK_LEFT, back);
+    view.draw_lines(
+        &[front_left, front_right, back_right, back_left, front_left],
+        color,
+        display,
+    )?;
+
+    let front = viewport.y(front);
+    view.draw_lines(&[Point::new(back_left.x, front), back_left], color, display)?;
+    view.draw_lines(
+        &[Point::new(back_right.x, front), back_right],
+        color,
+        display,
+    )
 }
 
-pub fn draw_front_door<D>(display: &mut D, showing: bool) -> Result<(), D::Error>
+pub fn draw_front_door<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    showing: bool,
+) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let color = color_for_door(showing);
+    draw_front_door_in(
+        display,
+        viewport,
+        &RoomView::CURRENT,
+        color_for_door(showing),
+    )
+}
 
-    const FD_FRONT_FRAME: &[Point] = &[
-        Point::new(FD_FRONT_LEFT, FD_FRONT_BOTTOM),
-        Point::new(FD_FRONT_LEFT, FD_FRONT_TOP),
-        Point::new(FD_FRONT_RIGHT, FD_FRONT_TOP),
-        Point::new(FD_FRONT_RIGHT, FD_FRONT_BOTTOM),
+fn draw_front_door_in<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    view: &RoomView,
+    color: D::Color,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let front_top_left = viewport.point(ORIGINAL_FD_FRONT_LEFT, ORIGINAL_FD_FRONT_TOP);
+    let front_top_right = viewport.point(ORIGINAL_FD_FRONT_RIGHT, ORIGINAL_FD_FRONT_TOP);
+    let front_bottom_left = viewport.point(ORIGINAL_FD_FRONT_LEFT, ORIGINAL_FD_FRONT_BOTTOM);
+    let front_bottom_right = viewport.point(ORIGINAL_FD_FRONT_RIGHT, ORIGINAL_FD_FRONT_BOTTOM);
+    let back_top_left = viewport.point(ORIGINAL_FD_BACK_LEFT, ORIGINAL_FD_BACK_TOP);
+    let back_top_right = viewport.point(ORIGINAL_FD_BACK_RIGHT, ORIGINAL_FD_BACK_TOP);
+    let back_bottom_left = viewport.point(ORIGINAL_FD_BACK_LEFT, ORIGINAL_FD_BACK_BOTTOM);
+    let back_bottom_right = viewport.point(ORIGINAL_FD_BACK_RIGHT, ORIGINAL_FD_BACK_BOTTOM);
+
+    let front_frame = [
+        front_bottom_left,
+        front_top_left,
+        front_top_right,
+        front_bottom_right,
     ];
-    draw_lines_with_color(FD_FRONT_FRAME, color, display)?;
-
-    const FD_BACK_FRAME: &[Point] = &[
-        Point::new(FD_BACK_LEFT, FD_BACK_TOP),
-        Point::new(FD_BACK_RIGHT, FD_BACK_TOP),
-        Point::new(FD_BACK_RIGHT, FD_BACK_BOTTOM),
-        Point::new(FD_BACK_LEFT, FD_BACK_BOTTOM),
-        Point::new(FD_BACK_LEFT, FD_BACK_TOP),
+    view.draw_lines(&front_frame, color, display)?;
+
+    let back_frame = [
+        back_top_left,
+        back_top_right,
+        back_bottom_right,
+        back_bottom_left,
+        back_top_left,
     ];
-    draw_lines_with_color(FD_BACK_FRAME, color, display)?;
+    view.draw_lines(&back_frame, color, display)?;
 
-    const FD_BOTTOM_LEFT: [Point; 2] = [
-        Point::new(FD_FRONT_LEFT, FD_FRONT_BOTTOM),
-        Point::new(FD_BACK_LEFT, FD_BACK_BOTTOM),
-    ];
-    draw_lines_with_color(&FD_BOTTOM_LEFT, color, display)?;
+    view.draw_lines(&[front_bottom_left, back_bottom_left], color, display)?;
+    view.draw_lines(&[front_bottom_right, back_bottom_right], color, display)?;
+    view.draw_lines(&[front_top_right, back_top_right], color, display)?;
+    view.draw_lines(&[front_top_left, back_top_left], color, display)?;
 
-    const FD_BOTTOM_RIGHT: [Point; 2] = [
-        Point::new(FD_FRONT_RIGHT, FD_FRONT_BOTTOM),
-        Point::new(FD_BACK_RIGHT, FD_BACK_BOTTOM),
+    // Redraw the two pixels that might have been erased by the
+    // left and right sides of the front door.
+    let fix_pixels = [
+        Pixel(front_bottom_right, D::Color::WALL),
+        Pixel(front_bottom_left, D::Color::WALL),
     ];
-    draw_lines_with_color(&FD_BOTTOM_RIGHT, color, display)?;
+    display.draw_iter(fix_pixels.map(|Pixel(point, color)| Pixel(view.point(point), color)))
+}
 
-    const FD_TOP_RIGHT: [Point; 2] = [
-        Point::new(FD_FRONT_RIGHT, FD_FRONT_TOP),
-        Point::new(FD_BACK_RIGHT, FD_BACK_TOP),
-    ];
-    draw_lines_with_color(&FD_TOP_RIGHT, color, display)?;
+/// The doors of a room, as seen facing one way.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RoomDoors {
+    pub left: bool,
+    pub front: bool,
+    pub right: bool,
+    pub top: bool,
+    pub bottom: bool,
+}
 
-    const FD_TOP_LEFT: [Point; 2] = [
-        Point::new(FD_FRONT_LEFT, FD_FRONT_TOP),
-        Point::new(FD_BACK_LEFT, FD_BACK_TOP),
-    ];
-    draw_lines_with_color(&FD_TOP_LEFT, color, display)?;
+/// How many rooms ahead can be seen through open front doors.
+pub const CORRIDOR_LENGTH: usize = 3;
 
-    // Redraw the two pixels that might have been erased by the
-    // left and right sides of the front door.
-    const FIX_PIXELS: [Pixel<Rgb565>; 2] = [
-        Pixel(Point::new(FD_FRONT_RIGHT, FD_FRONT_BOTTOM), Rgb565::BLACK),
-        Pixel(Point::new(FD_FRONT_LEFT, FD_FRONT_BOTTOM), Rgb565::BLACK),
-    ];
-    display.draw_iter(FIX_PIXELS)
+/// Draws the rooms seen through the current room's front door, nearest
+/// first, each through the doorway of the one before. The doorway is cleared
+/// first, so an empty corridor leaves it blank.
+pub fn draw_corridor<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    rooms: &[RoomDoors],
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let mut view = RoomView::CURRENT;
+    let doorway = view.doorway(viewport);
+    doorway
+        .into_styled(PrimitiveStyle::with_fill(D::Color::ROOM))
+        .draw(display)?;
+
+    let mut clip = doorway;
+    for room in rooms.iter().take(CORRIDOR_LENGTH) {
+        view = view.next(viewport);
+        let mut display = display.clipped(&clip);
+        draw_room_in(&mut display, viewport, &view)?;
+        let side_doors = [
+            (room.left, (ORIGINAL_LD_LEFT, ORIGINAL_LD_RIGHT)),
+            (room.right, (ORIGINAL_RD_LEFT, ORIGINAL_RD_RIGHT)),
+        ];
+        for (_, edges) in side_doors.into_iter().filter(|(door, _)| *door) {
+            draw_side_door_in(&mut display, viewport, &view, edges, D::Color::DOOR)?;
+        }
+        let floor_doors = [
+            (room.top, (ORIGINAL_TD_TOP, ORIGINAL_TD_BOTTOM)),
+            (room.bottom, (ORIGINAL_BD_TOP, ORIGINAL_BD_BOTTOM)),
+        ];
+        for (_, edges) in floor_doors.into_iter().filter(|(door, _)| *door) {
+            draw_floor_door_in(&mut display, viewport, &view, edges, D::Color::DOOR)?;
+        }
+        if !room.front {
+            break;
+        }
+        draw_front_door_in(&mut display, viewport, &view, D::Color::DOOR)?;
+        clip = clip.intersection(&view.doorway(viewport));
+    }
+    Ok(())
 }
 
-const STATUS_TOP: u32 = FRONT_BOTTOM as u32;
-const STATUS_HEIGHT: u32 = SCREEN_SIZE.height - STATUS_TOP;
-const STATUS_CENTER_V: i32 = (SCREEN_SIZE.height - STATUS_HEIGHT / 2 + 5) as i32;
+/// The status bar under the room, and the baselines of its two rows.
+struct StatusBar {
+    area: Rectangle,
+    row_1: i32,
+    row_2: i32,
+}
+
+impl StatusBar {
+    fn new(viewport: &Viewport) -> Self {
+        let top = viewport.y(ORIGINAL_FRONT_BOTTOM);
+        let height = (viewport.bottom(0) - top).max(0);
+        let middle = top + height / 2;
+        Self {
+            area: Rectangle::new(
+                Point::new(viewport.left(0), top),
+                Size::new(viewport.size.width, height as u32),
+            ),
+            row_1: middle - 4,
+            row_2: middle + 13,
+        }
+    }
+}
 
 pub fn draw_status<D>(
     display: &mut D,
+    viewport: &Viewport,
     facing: Direction,
     position: Option<Coord>,
-    hint: Option<Direction>,
+    hint: Option<&str>,
     elapsed: u64,
+    score: &Score,
 ) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
     let style = PrimitiveStyleBuilder::new()
-        .fill_color(Rgb565::BLACK)
+        .fill_color(D::Color::BACKGROUND)
         .build();
 
-    Rectangle::new(
-        Point::new(0, FRONT_BOTTOM),
-        Size::new(SCREEN_SIZE.width, STATUS_HEIGHT),
-    )
-    .into_styled(style)
-    .draw(display)?;
+    StatusBar::new(viewport)
+        .area
+        .into_styled(style)
+        .draw(display)?;
 
-    update_status(display, facing, position, hint, elapsed)?;
+    update_status(display, viewport, facing, position, hint, elapsed, score)?;
 
     Ok(())
 }
 
 pub fn update_status<D>(
     display: &mut D,
+    viewport: &Viewport,
     facing: Direction,
     position: Option<Coord>,
-    hint: Option<Direction>,
+    hint: Option<&str>,
     elapsed: u64,
+    score: &Score,
 ) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
     let style = MonoTextStyleBuilder::new()
         .font(&FONT_8X13_BOLD)
-        .text_color(Rgb565::WHITE)
-        .background_color(Rgb565::BLACK)
+        .text_color(D::Color::TEXT)
+        .background_color(D::Color::BACKGROUND)
         .build();
+    let status = StatusBar::new(viewport);
 
     if let Some(hint) = hint {
         let mut label = String::<32>::new();
         let facing_str: &str = facing.into();
-        let hint_str: &str = hint.into();
-        fmt::write(&mut label, format_args!("{}[{}]", facing_str, hint_str)).expect("write");
+        fmt::write(&mut label, format_args!("{}[{}]", facing_str, hint)).expect("write");
         Text::with_alignment(
             &label,
-            Point::new((SCREEN_SIZE.width / 2) as i32, STATUS_CENTER_V),
+            Point::new(viewport.center_x(), status.row_1),
             style,
             Alignment::Center,
         )
@@ -422,14 +662,15 @@ where
     } else {
         Text::with_alignment(
             facing.into(),
-            Point::new((SCREEN_SIZE.width / 2) as i32, STATUS_CENTER_V),
+            Point::new(viewport.center_x(), status.row_1),
             style,
             Alignment::Center,
         )
         .draw(display)?;
     }
 
-    update_time(display, elapsed)?;
+    update_time(display, viewport, elapsed)?;
+    update_score(display, viewport, score)?;
 
     if let Some(position) = position {
         let mut label = String::<12>::new();
@@ -440,7 +681,7 @@ where
         .expect("format");
         Text::with_alignment(
             &label,
-            Point::new((SCREEN_SIZE.width - 5) as i32, STATUS_CENTER_V),
+            Point::new(viewport.right(5), status.row_1),
             style,
             Alignment::Right,
         )
@@ -450,15 +691,58 @@ where
     Ok(())
 }
 
-pub fn update_time<D>(display: &mut D, elapsed: u64) -> Result<(), D::Error>
+/// Draws moves against par and the score so far on the second status row.
+/// Both are padded so that shorter numbers cover longer ones.
+pub fn update_score<D>(display: &mut D, viewport: &Viewport, score: &Score) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let style = MonoTextStyleBuilder::new()
+        .font(&FONT_8X13_BOLD)
+        .text_color(D::Color::TEXT)
+        .background_color(D::Color::BACKGROUND)
+        .build();
+    let status = StatusBar::new(viewport);
+
+    let mut moves_label = String::<32>::new();
+    fmt::write(
+        &mut moves_label,
+        format_args!("Moves:{:4} Par:{:3}", score.tally.moves, score.par),
+    )
+    .expect("write");
+    Text::with_alignment(
+        &moves_label,
+        Point::new(viewport.left(5), status.row_2),
+        style,
+        Alignment::Left,
+    )
+    .draw(display)?;
+
+    let mut score_label = String::<16>::new();
+    fmt::write(&mut score_label, format_args!("Score:{:5}", score.points())).expect("write");
+    Text::with_alignment(
+        &score_label,
+        Point::new(viewport.right(5), status.row_2),
+        style,
+        Alignment::Right,
+    )
+    .draw(display)?;
+
+    Ok(())
+}
+
+pub fn update_time<D>(display: &mut D, viewport: &Viewport, elapsed: u64) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
     let style = MonoTextStyleBuilder::new()
         .font(&FONT_8X13_BOLD)
-        .text_color(Rgb565::WHITE)
-        .background_color(Rgb565::BLACK)
+        .text_color(D::Color::TEXT)
+        .background_color(D::Color::BACKGROUND)
         .build();
+    let status = StatusBar::new(viewport);
 
     let mut time_label = String::<32>::new();
     let seconds = (elapsed + 999) / 1000;
@@ -469,7 +753,7 @@ where
     .expect("write");
     let time = Text::with_alignment(
         &time_label,
-        Point::new(5, STATUS_CENTER_V),
+        Point::new(viewport.left(5), status.row_1),
         style,
         Alignment::Left,
     );
@@ -478,16 +762,17 @@ where
     Ok(())
 }
 
-pub fn draw_win<D>(display: &mut D) -> Result<(), D::Error>
+fn draw_message<D>(display: &mut D, viewport: &Viewport, message: &str) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let style = MonoTextStyle::new(&FONT_8X13_BOLD, Rgb565::WHITE);
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
     Text::with_alignment(
-        "You Win!",
+        message,
         Point::new(
-            (SCREEN_SIZE.width / 2) as i32,
-            (SCREEN_SIZE.height / 2) as i32,
+            viewport.center_x(),
+            viewport.top((viewport.size.height / 2) as i32),
         ),
         style,
         Alignment::Center,
@@ -495,10 +780,172 @@ where
     .draw(display)?;
     Text::with_alignment(
         "Press any key to continue",
-        Point::new(
-            (SCREEN_SIZE.width / 2) as i32,
-            (SCREEN_SIZE.height - 30) as i32,
+        Point::new(viewport.center_x(), viewport.bottom(30)),
+        style,
+        Alignment::Center,
+    )
+    .draw(display)?;
+    Ok(())
+}
+
+const RESULTS_TEXT_TOP: f32 = lcd_y_to_ratio!(55.0);
+/// Between the baseline of the last line of text and the top of the map.
+const RESULTS_MAP_SPACING: i32 = 20;
+/// Between the bottom of the map and the legend, for the floor numbers.
+const RESULTS_LABELS_HEIGHT: i32 = 25;
+const RESULTS_MAP_GAP: u32 = 6;
+const RESULTS_MIN_ROOM_SIZE: u32 = 2;
+const RESULTS_MAX_ROOM_SIZE: u32 = 16;
+const RESULTS_LEGEND_BOTTOM: i32 = 35;
+
+const LEGEND_SWATCH_SIZE: u32 = 9;
+
+/// The color and fill of a room on the results map, by whether the player
+/// went through it and whether it's on the shortest way out.
+fn color_for_result_room<C: Palette>(visited: bool, shortest: bool) -> (C, Fill) {
+    match (visited, shortest) {
+        (true, true) => (C::HIGHLIGHT, Fill::Solid),
+        (true, false) => (C::ROUTE, C::ROUTE_FILL),
+        (false, true) => (C::MARKER, C::MARKER_FILL),
+        (false, false) => (C::SHADE, C::SHADE_FILL),
+    }
+}
+
+/// Fills `area` with `color`, leaving the pixels the fill skips alone.
+fn fill_area<D>(
+    display: &mut D,
+    area: Rectangle,
+    color: D::Color,
+    fill: Fill,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+{
+    match fill {
+        Fill::Solid => area
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(display),
+        Fill::Outline => area
+            .into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(display),
+        Fill::Checkered | Fill::Dotted => display.draw_iter(
+            area.points()
+                .filter(|point| match fill {
+                    Fill::Dotted => point.x % 2 == 0 && point.y % 2 == 0,
+                    _ => (point.x + point.y) % 2 == 0,
+                })
+                .map(|point| Pixel(point, color)),
         ),
+    }
+}
+
+/// Draws labels in their colors, spread evenly across the screen, each
+/// after a swatch of its fill if it has one.
+fn draw_legend<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    top: i32,
+    labels: &[(&str, D::Color, Option<Fill>)],
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let spacing = viewport.size.width / (labels.len() as u32 + 1);
+    for (index, (text, color, fill)) in labels.iter().enumerate() {
+        let center = Point::new(viewport.left((spacing * (index as u32 + 1)) as i32), top);
+        let text = Text::with_alignment(
+            text,
+            center,
+            MonoTextStyle::new(&FONT_8X13_BOLD, *color),
+            Alignment::Center,
+        );
+        if let Some(fill) = fill {
+            let text_box = text.bounding_box();
+            let swatch = Rectangle::new(
+                Point::new(
+                    text_box.top_left.x - LEGEND_SWATCH_SIZE as i32 - 4,
+                    text_box.center().y - LEGEND_SWATCH_SIZE as i32 / 2,
+                ),
+                Size::new(LEGEND_SWATCH_SIZE, LEGEND_SWATCH_SIZE),
+            );
+            fill_area(display, swatch, *color, *fill)?;
+        }
+        text.draw(display)?;
+    }
+    Ok(())
+}
+
+/// Draws the results of a won game: the numbers, then a map of each floor
+/// comparing the rooms the player went through with the shortest way out.
+pub fn draw_results<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    result: &GameResult,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_title(display, viewport, "You Win!")?;
+
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    let score = &result.score;
+    let seconds = score.elapsed.div_ceil(1000);
+    let tally = &score.tally;
+    let mut lines: [String<40>; 4] = Default::default();
+    fmt::write(
+        &mut lines[0],
+        format_args!(
+            "Time {}:{:02}  Score {}",
+            seconds / 60,
+            seconds % 60,
+            score.points()
+        ),
+    )
+    .expect("write");
+    fmt::write(
+        &mut lines[1],
+        format_args!("Moves {} (shortest {})", tally.moves, score.par),
+    )
+    .expect("write");
+    fmt::write(
+        &mut lines[2],
+        format_args!(
+            "Hints {}  Turns {}  Bumps {}",
+            tally.hints, tally.turns, tally.bumps
+        ),
+    )
+    .expect("write");
+    fmt::write(
+        &mut lines[3],
+        format_args!(
+            "Rooms visited {} of {}",
+            result.visited.len(),
+            result.room_count()
+        ),
+    )
+    .expect("write");
+    let text_top = viewport.y(RESULTS_TEXT_TOP);
+    for (index, line) in lines.iter().enumerate() {
+        Text::with_alignment(
+            line,
+            Point::new(
+                viewport.center_x(),
+                text_top + index as i32 * TEXT_LINE_HEIGHT,
+            ),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+
+    let map_top = text_top + (lines.len() as i32 - 1) * TEXT_LINE_HEIGHT + RESULTS_MAP_SPACING;
+    draw_results_maps(display, viewport, map_top, result)?;
+
+    Text::with_alignment(
+        "Press any key to continue",
+        Point::new(viewport.center_x(), viewport.bottom(15)),
         style,
         Alignment::Center,
     )
@@ -506,20 +953,910 @@ where
     Ok(())
 }
 
-pub fn draw_start<D>(display: &mut D) -> Result<(), D::Error>
+/// Draws a map of each floor side by side from `map_top`, with the rooms as
+/// big as will fit above the legend, or nothing if they'd be too small to
+/// make out.
+fn draw_results_maps<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    map_top: i32,
+    result: &GameResult,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let legend_top = viewport.bottom(RESULTS_LEGEND_BOTTOM);
+    let map_height = (legend_top - RESULTS_LABELS_HEIGHT - map_top).max(0) as u32;
+    let (width, height, floors) = result.dimensions;
+    let (width, height, floors) = (width as u32, height as u32, floors as u32);
+    let gaps = RESULTS_MAP_GAP * (floors - 1);
+    let room_size = (viewport.size.width.saturating_sub(20 + gaps) / (width * floors))
+        .min(map_height / height)
+        .min(RESULTS_MAX_ROOM_SIZE);
+    if room_size < RESULTS_MIN_ROOM_SIZE {
+        return Ok(());
+    }
+    let floor_width = room_size * width;
+    let maps_width = floor_width * floors + gaps;
+    let maps_left = viewport.left((viewport.size.width.saturating_sub(maps_width) / 2) as i32);
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    let mut label = String::<4>::new();
+    for z in 0..floors {
+        let floor_left = maps_left + (z * (floor_width + RESULTS_MAP_GAP)) as i32;
+        draw_results_floor(
+            display,
+            Point::new(floor_left, map_top),
+            room_size,
+            z as isize,
+            result,
+        )?;
+
+        label.clear();
+        fmt::write(&mut label, format_args!("{}", z + 1)).expect("write");
+        Text::with_alignment(
+            &label,
+            Point::new(
+                floor_left + floor_width as i32 / 2,
+                map_top + (room_size * height) as i32 + 14,
+            ),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+
+    draw_legend(
+        display,
+        viewport,
+        legend_top,
+        &[
+            result_legend_label("Yours", true, false),
+            result_legend_label("Shortest", false, true),
+            result_legend_label("Both", true, true),
+        ],
+    )
+}
+
+fn result_legend_label<C: Palette>(
+    text: &str,
+    visited: bool,
+    shortest: bool,
+) -> (&str, C, Option<Fill>) {
+    let (color, fill) = color_for_result_room(visited, shortest);
+    (text, color, Some(fill))
+}
+
+/// Draws the rooms of one floor of the results map from `top_left`.
+fn draw_results_floor<D>(
+    display: &mut D,
+    top_left: Point,
+    room_size: u32,
+    z: isize,
+    result: &GameResult,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let (width, height, _) = result.dimensions;
+    let room = Size::new(room_size - 1, room_size - 1);
+    for y in 0..height {
+        for x in 0..width {
+            let coord = Coord {
+                x: x as isize,
+                y: y as isize,
+                z,
+            };
+            let (color, fill) = color_for_result_room(
+                result.visited.contains(&coord),
+                result.shortest.contains(&coord),
+            );
+            let room_top_left =
+                top_left + Point::new((x as u32 * room_size) as i32, (y as u32 * room_size) as i32);
+            fill_area(display, Rectangle::new(room_top_left, room), color, fill)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn draw_out_of_time<D>(display: &mut D, viewport: &Viewport) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_message(display, viewport, "Out of time!")
+}
+
+const MENU_TITLE_TOP: f32 = lcd_y_to_ratio!(30.0);
+const MENU_TEXT_TOP: i32 = 25;
+const MENU_ITEMS_TOP: f32 = lcd_y_to_ratio!(75.0);
+const MENU_ITEMS_BOTTOM: i32 = 10;
+const MENU_LINE_HEIGHT: i32 = 22;
+const TEXT_LINE_HEIGHT: i32 = 15;
+
+fn draw_title<D>(display: &mut D, viewport: &Viewport, title: &str) -> Result<(), D::Error>
 where
-    D: DrawTarget<Color = Rgb565>,
+    D: DrawTarget,
+    D::Color: Palette,
 {
-    let style = MonoTextStyle::new(&FONT_8X13_BOLD, Rgb565::WHITE);
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TITLE);
     Text::with_alignment(
-        "Press any key to start",
-        Point::new(
-            (SCREEN_SIZE.width / 2) as i32,
-            (SCREEN_SIZE.height / 2) as i32,
-        ),
+        title,
+        Point::new(viewport.center_x(), viewport.y(MENU_TITLE_TOP)),
+        style,
+        Alignment::Center,
+    )
+    .draw(display)?;
+    Ok(())
+}
+
+/// Draws a menu with the `selected` item highlighted. Items whose flag is
+/// false can't be chosen and are greyed out.
+pub fn draw_menu<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    title: &str,
+    items: &[(&str, bool)],
+    selected: usize,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_title(display, viewport, title)?;
+
+    // Closer together if they wouldn't all fit.
+    let top = viewport.y(MENU_ITEMS_TOP);
+    let line_height = ((viewport.bottom(MENU_ITEMS_BOTTOM) - top)
+        / (items.len() as i32 - 1).max(1))
+    .clamp(TEXT_LINE_HEIGHT, MENU_LINE_HEIGHT);
+    for (index, (item, enabled)) in items.iter().enumerate() {
+        let text_color = if *enabled {
+            D::Color::TEXT
+        } else {
+            D::Color::DISABLED
+        };
+        let mut style = MonoTextStyleBuilder::new().font(&FONT_8X13_BOLD);
+        style = if index == selected {
+            style
+                .text_color(D::Color::BACKGROUND)
+                .background_color(text_color)
+        } else {
+            style.text_color(text_color)
+        };
+        if !enabled && D::Color::STRIKE_DISABLED {
+            style = style.strikethrough();
+        }
+        let style = style.build();
+
+        let mut label = String::<40>::new();
+        fmt::write(&mut label, format_args!(" {} ", item)).expect("write");
+        Text::with_alignment(
+            &label,
+            Point::new(viewport.center_x(), top + index as i32 * line_height),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+    Ok(())
+}
+
+/// Draws a page of text under a title, for things like the instructions.
+pub fn draw_text_page<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    title: &str,
+    lines: &[&str],
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_title(display, viewport, title)?;
+
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    for (index, line) in lines.iter().enumerate() {
+        Text::with_alignment(
+            line,
+            Point::new(
+                viewport.left(5),
+                viewport.y(MENU_TITLE_TOP) + MENU_TEXT_TOP + index as i32 * TEXT_LINE_HEIGHT,
+            ),
+            style,
+            Alignment::Left,
+        )
+        .draw(display)?;
+    }
+    Text::with_alignment(
+        "Press any key to go back",
+        Point::new(viewport.center_x(), viewport.bottom(15)),
+        style,
+        Alignment::Center,
+    )
+    .draw(display)?;
+    Ok(())
+}
+
+/// Draws the high score table for one difficulty, with the `highlight` place
+/// picked out.
+pub fn draw_high_scores<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    difficulty: &str,
+    table: &[HighScore],
+    highlight: Option<usize>,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let mut title = String::<32>::new();
+    fmt::write(&mut title, format_args!("High Scores: {}", difficulty)).expect("write");
+    draw_title(display, viewport, &title)?;
+
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    if table.is_empty() {
+        Text::with_alignment(
+            "No high scores yet.",
+            Point::new(viewport.center_x(), viewport.y(MENU_ITEMS_TOP)),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+    let mut line = String::<32>::new();
+    for (place, entry) in table.iter().enumerate().take(HighScores::PLACES) {
+        line.clear();
+        fmt::write(
+            &mut line,
+            format_args!(
+                "{:2}. {}  {:5}  {:3}:{:02}",
+                place + 1,
+                entry.initials(),
+                entry.points,
+                entry.seconds / 60,
+                entry.seconds % 60
+            ),
+        )
+        .expect("write");
+        let color = if Some(place) == highlight {
+            D::Color::HIGHLIGHT
+        } else {
+            D::Color::TEXT
+        };
+        Text::with_alignment(
+            &line,
+            Point::new(
+                viewport.center_x(),
+                viewport.y(MENU_TITLE_TOP) + MENU_TEXT_TOP + place as i32 * TEXT_LINE_HEIGHT,
+            ),
+            MonoTextStyle::new(&FONT_8X13_BOLD, color),
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+
+    draw_footer(
+        display,
+        viewport,
+        &["Turn for another difficulty,", "any other key to go back"],
+    )
+}
+
+/// Draws lines of help text at the bottom of the screen.
+fn draw_footer<D>(display: &mut D, viewport: &Viewport, lines: &[&str]) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    let top = viewport.bottom(15) - (lines.len() as i32 - 1) * TEXT_LINE_HEIGHT;
+    for (index, line) in lines.iter().enumerate() {
+        Text::with_alignment(
+            line,
+            Point::new(viewport.center_x(), top + index as i32 * TEXT_LINE_HEIGHT),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+    Ok(())
+}
+
+const INITIALS_TOP: f32 = lcd_y_to_ratio!(120.0);
+const INITIALS_HELP_TOP: f32 = lcd_y_to_ratio!(165.0);
+const INITIALS_SPACING: i32 = 30;
+
+/// Draws the initials being entered for a new high score, with the letter
+/// at `cursor` highlighted.
+pub fn draw_initials<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    difficulty: &str,
+    place: usize,
+    initials: &[u8; 3],
+    cursor: usize,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_title(display, viewport, "New High Score!")?;
+
+    let style = MonoTextStyle::new(&FONT_8X13_BOLD, D::Color::TEXT);
+    let mut line = String::<32>::new();
+    fmt::write(
+        &mut line,
+        format_args!("Number {} on {}", place + 1, difficulty),
+    )
+    .expect("write");
+    Text::with_alignment(
+        &line,
+        Point::new(viewport.center_x(), viewport.y(MENU_ITEMS_TOP)),
         style,
         Alignment::Center,
     )
     .draw(display)?;
+
+    let first = viewport.center_x() - INITIALS_SPACING;
+    for (index, letter) in initials.iter().enumerate() {
+        let letter_style = if index == cursor {
+            MonoTextStyleBuilder::new()
+                .font(&FONT_10X20)
+                .text_color(D::Color::BACKGROUND)
+                .background_color(D::Color::MARKER)
+                .build()
+        } else {
+            MonoTextStyle::new(&FONT_10X20, D::Color::TEXT)
+        };
+        let mut text = [0; 4];
+        Text::with_alignment(
+            char::from(*letter).encode_utf8(&mut text),
+            Point::new(
+                first + index as i32 * INITIALS_SPACING,
+                viewport.y(INITIALS_TOP),
+            ),
+            letter_style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
+
+    for (index, text) in [
+        "Turn to change the letter,",
+        "move forward for the next",
+        "and left to go back.",
+    ]
+    .iter()
+    .enumerate()
+    {
+        Text::with_alignment(
+            text,
+            Point::new(
+                viewport.center_x(),
+                viewport.y(INITIALS_HELP_TOP) + index as i32 * TEXT_LINE_HEIGHT,
+            ),
+            style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+    }
     Ok(())
 }
+
+/// What the player has found out about one room, for the map.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MapRoom {
+    pub visited: bool,
+    /// The sides the player has seen, as `Direction` bits.
+    pub seen: u8,
+    /// The doors among the sides seen.
+    pub doors: u8,
+}
+
+impl MapRoom {
+    /// Whether there's a door in `direction`, if the player has seen that
+    /// side.
+    pub fn door(&self, direction: Direction) -> Option<bool> {
+        let bit = 1 << direction as u8;
+        (self.seen & bit != 0).then_some(self.doors & bit != 0)
+    }
+}
+
+const MAP_TOP: f32 = lcd_y_to_ratio!(50.0);
+/// Left below the map for the footer.
+const MAP_BOTTOM: i32 = 40;
+const MAP_MAX_ROOM_SIZE: u32 = 40;
+
+/// Draws a filled triangle around `center` pointing in `direction`, where up
+/// and down point north and south.
+fn draw_arrow<D>(
+    display: &mut D,
+    center: Point,
+    radius: i32,
+    direction: Direction,
+    color: D::Color,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let (tip, side) = match direction {
+        Direction::North | Direction::Up => (Point::new(0, -radius), Point::new(radius, 0)),
+        Direction::South | Direction::Down => (Point::new(0, radius), Point::new(radius, 0)),
+        Direction::East => (Point::new(radius, 0), Point::new(0, radius)),
+        Direction::West => (Point::new(-radius, 0), Point::new(0, radius)),
+    };
+    Triangle::new(center + tip, center - tip + side, center - tip - side)
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
+}
+
+/// Draws a top-down map of one floor. Walls and doors show once seen, with
+/// doors as gaps, and arrows mark the doors up and down. `player` is the
+/// room and facing of the player, if they're on this floor.
+pub fn draw_map<D>(
+    display: &mut D,
+    viewport: &Viewport,
+    floor: usize,
+    (width, height, floors): (usize, usize, usize),
+    room: impl Fn(usize, usize) -> MapRoom,
+    player: Option<(usize, usize, Direction)>,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    let mut title = String::<32>::new();
+    fmt::write(
+        &mut title,
+        format_args!("Floor {} of {}", floor + 1, floors),
+    )
+    .expect("write");
+    draw_title(display, viewport, &title)?;
+
+    let top = viewport.y(MAP_TOP);
+    let map_height = (viewport.bottom(MAP_BOTTOM) - top).max(0) as u32;
+    let room_size = (map_height / height as u32)
+        .min(viewport.size.width.saturating_sub(20) / width as u32)
+        .clamp(1, MAP_MAX_ROOM_SIZE) as i32;
+    let left = viewport.left((viewport.size.width as i32 - room_size * width as i32) / 2);
+    Rectangle::new(
+        Point::new(left, top),
+        Size::new(
+            (room_size * width as i32) as u32 + 1,
+            (room_size * height as i32) as u32 + 1,
+        ),
+    )
+    .into_styled(PrimitiveStyle::with_stroke(D::Color::SHADE, 1))
+    .draw(display)?;
+
+    let wall_style = PrimitiveStyle::with_stroke(D::Color::TEXT, 1);
+    for y in 0..height {
+        for x in 0..width {
+            let map_room = room(x, y);
+            let top_left = Point::new(left + x as i32 * room_size, top + y as i32 * room_size);
+            if map_room.visited {
+                let area = Rectangle::new(
+                    top_left + Point::new(1, 1),
+                    Size::new(room_size as u32 - 1, room_size as u32 - 1),
+                );
+                fill_area(display, area, D::Color::SHADE, D::Color::SHADE_FILL)?;
+            }
+
+            let size = room_size;
+            let sides = [
+                (Direction::North, Point::zero(), Point::new(size, 0)),
+                (
+                    Direction::South,
+                    Point::new(0, size),
+                    Point::new(size, size),
+                ),
+                (Direction::West, Point::zero(), Point::new(0, size)),
+                (Direction::East, Point::new(size, 0), Point::new(size, size)),
+            ];
+            for (direction, start, end) in sides {
+                let (start, end) = (top_left + start, top_left + end);
+                match map_room.door(direction) {
+                    Some(false) => Line::new(start, end)
+                        .into_styled(wall_style)
+                        .draw(display)?,
+                    Some(true) => {
+                        let third = (end - start) / 3;
+                        Line::new(start, start + third)
+                            .into_styled(wall_style)
+                            .draw(display)?;
+                        Line::new(end - third, end)
+                            .into_styled(wall_style)
+                            .draw(display)?;
+                    }
+                    None => (),
+                }
+            }
+
+            let marker = size / 8;
+            if map_room.door(Direction::Up) == Some(true) {
+                let center = top_left + Point::new(size / 4, size / 4);
+                draw_arrow(display, center, marker, Direction::Up, D::Color::ROUTE)?;
+            }
+            if map_room.door(Direction::Down) == Some(true) {
+                let center = top_left + Point::new(size * 3 / 4, size * 3 / 4);
+                draw_arrow(display, center, marker, Direction::Down, D::Color::ROUTE)?;
+            }
+            if let Some((_, _, facing)) = player.filter(|(px, py, _)| (*px, *py) == (x, y)) {
+                let center = top_left + Point::new(size / 2, size / 2);
+                draw_arrow(display, center, size / 5, facing, D::Color::MARKER)?;
+            }
+        }
+    }
+
+    draw_footer(
+        display,
+        viewport,
+        &["Up and down change floor,", "any other key to go back"],
+    )
+}
+
+/// How much further apart floors are than rooms on the screen.
+const ISOMETRIC_FLOOR_SPACING: f32 = 3.0;
+
+/// Draws the whole maze as an isometric stack of floors, fitted to the
+/// display: the passages on each floor, the shafts between floors and the
+/// door out. The rooms of the player's `route` and the `solution` are drawn
+/// over the top when given.
+pub fn draw_isometric<D, const X: usize, const Y: usize, const Z: usize>(
+    display: &mut D,
+    maze: &Maze<X, Y, Z>,
+    solution: Option<&SolutionPath<X, Y, Z>>,
+    route: Option<&RoomSet>,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    // Rooms are half a unit from the edge of their floor, and from the ends
+    // of the stack, in each direction.
+    let area = display.bounding_box();
+    let width = area.size.width.saturating_sub(1) as f32;
+    let height = area.size.height.saturating_sub(1) as f32;
+    let across = (X + Y) as f32;
+    let scale = (width / across / 2.0).min(height / (across + ISOMETRIC_FLOOR_SPACING * Z as f32));
+    let (run, rise, lift) = (2.0 * scale, scale, ISOMETRIC_FLOOR_SPACING * scale);
+    let left = area.top_left.x as f32 + (width - across * run) / 2.0;
+    let top = area.top_left.y as f32 + (height - across * rise - Z as f32 * lift) / 2.0;
+    let origin_x = left + Y as f32 * run;
+    let origin_y = top + rise + (Z as f32 - 0.5) * lift;
+    let project = |x: f32, y: f32, z: f32| {
+        Point::new(
+            (origin_x + (x - y) * run) as i32,
+            (origin_y + (x + y) * rise - z * lift) as i32,
+        )
+    };
+    let center = |coord: &Coord| project(coord.x as f32, coord.y as f32, coord.z as f32);
+    // Stops halfway to a position outside the maze, at the door out.
+    let segment = |from: &Coord, to: &Coord| {
+        if Maze::<X, Y, Z>::is_outside(to) {
+            let middle = |from: isize, to: isize| (from + to) as f32 / 2.0;
+            let door = project(
+                middle(from.x, to.x),
+                middle(from.y, to.y),
+                middle(from.z, to.z),
+            );
+            Line::new(center(from), door)
+        } else {
+            Line::new(center(from), center(to))
+        }
+    };
+
+    for z in 0..Z {
+        let floor = z as f32;
+        let (far_x, far_y) = (X as f32 - 0.5, Y as f32 - 0.5);
+        let corners = [
+            project(-0.5, -0.5, floor),
+            project(far_x, -0.5, floor),
+            project(far_x, far_y, floor),
+            project(-0.5, far_y, floor),
+            project(-0.5, -0.5, floor),
+        ];
+        draw_lines_with_color(&corners, D::Color::SHADE, display)?;
+
+        let style = PrimitiveStyle::with_stroke(D::Color::FLOORS[z % D::Color::FLOORS.len()], 1);
+        for y in 0..Y {
+            for x in 0..X {
+                let coord = Coord {
+                    x: x as isize,
+                    y: y as isize,
+                    z: z as isize,
+                };
+                let cell = maze.get_cell(&coord);
+                for direction in Direction::ALL {
+                    let next = coord.move_in_direction(direction);
+                    let is_passage = matches!(direction, Direction::East | Direction::South);
+                    if cell.has_door(direction)
+                        && (is_passage || Maze::<X, Y, Z>::is_outside(&next))
+                    {
+                        segment(&coord, &next).into_styled(style).draw(display)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let shaft_style = PrimitiveStyle::with_stroke(D::Color::TEXT, 1);
+    for index in 0..Maze::<X, Y, Z>::cell_count() {
+        let coord = Maze::<X, Y, Z>::coord_at(index);
+        let above = coord.move_in_direction(Direction::Up);
+        if maze.get_cell(&coord).has_door(Direction::Up) && !Maze::<X, Y, Z>::is_outside(&above) {
+            segment(&coord, &above)
+                .into_styled(shaft_style)
+                .draw(display)?;
+        }
+    }
+
+    if let Some(route) = route {
+        let lines = || {
+            (0..Maze::<X, Y, Z>::cell_count()).flat_map(|index| {
+                let coord = Maze::<X, Y, Z>::coord_at(index);
+                let cell = maze.get_cell(&coord);
+                [Direction::East, Direction::South, Direction::Up]
+                    .into_iter()
+                    .filter(move |direction| cell.has_door(*direction))
+                    .map(move |direction| (coord, coord.move_in_direction(direction)))
+                    .filter(|(coord, next)| route.contains(coord) && route.contains(next))
+                    .map(|(coord, next)| segment(&coord, &next))
+            })
+        };
+        draw_lines_with_fill(display, lines, D::Color::ROUTE, 3, D::Color::ROUTE_FILL)?;
+    }
+
+    if let Some(solution) = solution {
+        let lines = || {
+            solution
+                .iter()
+                .zip(solution.iter().skip(1))
+                .map(|(to, from)| segment(from, to))
+        };
+        draw_lines_with_fill(display, lines, D::Color::MARKER, 1, D::Color::MARKER_FILL)?;
+    }
+    Ok(())
+}
+
+/// Draws each line `lines` makes, `width` pixels wide, in the style of
+/// `fill` so they can be told apart from lines in the same color: hollow for
+/// `Outline`, and broken for `Checkered` and `Dotted`.
+fn draw_lines_with_fill<D, I>(
+    display: &mut D,
+    lines: impl Fn() -> I,
+    color: D::Color,
+    width: u32,
+    fill: Fill,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+    I: Iterator<Item = Line>,
+{
+    match fill {
+        Fill::Solid | Fill::Outline => {
+            for line in lines() {
+                line.into_styled(PrimitiveStyle::with_stroke(color, width))
+                    .draw(display)?;
+            }
+            // Hollowed out once they're all drawn, so they stay hollow where
+            // they meet.
+            if fill == Fill::Outline && width > 2 {
+                let style = PrimitiveStyle::with_stroke(D::Color::BACKGROUND, width - 2);
+                for line in lines() {
+                    line.into_styled(style).draw(display)?;
+                }
+            }
+            Ok(())
+        }
+        Fill::Checkered | Fill::Dotted => {
+            let every = if fill == Fill::Dotted { 4 } else { 2 };
+            for line in lines() {
+                display.draw_iter(
+                    line.points()
+                        .step_by(every)
+                        .map(|point| Pixel(point, color)),
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+const REVEAL_TOP: i32 = 40;
+const REVEAL_BOTTOM: i32 = 45;
+
+/// Shows the whole of a finished maze, with the way the player went and the
+/// shortest way out.
+pub fn draw_reveal<D, const X: usize, const Y: usize, const Z: usize>(
+    display: &mut D,
+    viewport: &Viewport,
+    maze: &Maze<X, Y, Z>,
+    solution: Option<&SolutionPath<X, Y, Z>>,
+    route: &RoomSet,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    draw_title(display, viewport, "The Whole Maze")?;
+    let area = Rectangle::new(
+        Point::new(viewport.left(0), viewport.top(REVEAL_TOP)),
+        Size::new(
+            viewport.size.width,
+            viewport
+                .size
+                .height
+                .saturating_sub((REVEAL_TOP + REVEAL_BOTTOM) as u32),
+        ),
+    );
+    draw_isometric(&mut display.cropped(&area), maze, solution, Some(route))?;
+    draw_legend(
+        display,
+        viewport,
+        viewport.bottom(REVEAL_BOTTOM - 10),
+        &[
+            ("Yours", D::Color::ROUTE, Some(D::Color::ROUTE_FILL)),
+            ("Shortest", D::Color::MARKER, Some(D::Color::MARKER_FILL)),
+        ],
+    )?;
+    draw_footer(display, viewport, &["Press any key to continue"])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::maze::{solve, MazeGenerator};
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        pixelcolor::{BinaryColor, Rgb565},
+    };
+
+    #[test]
+    fn test_viewport() {
+        let viewport = Viewport::new(Point::new(4, 6), Size::new(56, 48));
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        draw_room(&mut display, &viewport).expect("draw");
+        draw_left_door(&mut display, &viewport, true).expect("draw");
+        draw_right_door(&mut display, &viewport, true).expect("draw");
+        draw_top_door(&mut display, &viewport, true).expect("draw");
+        draw_bottom_door(&mut display, &viewport, true).expect("draw");
+        draw_front_door(&mut display, &viewport, true).expect("draw");
+        let open = RoomDoors {
+            front: true,
+            ..Default::default()
+        };
+        draw_corridor(&mut display, &viewport, &[open; CORRIDOR_LENGTH]).expect("draw");
+
+        // The front of the room fills the width of the viewport.
+        let area = display.affected_area();
+        assert_eq!(area.top_left, viewport.top_left);
+        assert_eq!(area.size.width, viewport.size.width);
+        assert!(area.size.height < viewport.size.height);
+        assert_eq!(display.get_pixel(Point::new(59, 6)), Some(Rgb565::BLACK));
+    }
+
+    #[test]
+    fn test_map_fits_small_viewport() {
+        // The map stays above the footer rather than where it would be on
+        // the LCD.
+        let viewport = Viewport::new(Point::zero(), Size::new(64, 64));
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        display.set_allow_out_of_bounds_drawing(true);
+        draw_map(
+            &mut display,
+            &viewport,
+            0,
+            (3, 3, 3),
+            |_, _| MapRoom::default(),
+            None,
+        )
+        .expect("draw");
+        let outline = || {
+            display
+                .bounding_box()
+                .points()
+                .filter(|point| display.get_pixel(*point) == Some(Rgb565::SHADE))
+        };
+        assert!(outline().next().is_some());
+        assert!(outline().all(|point| point.y < viewport.bottom(MAP_BOTTOM)));
+    }
+
+    #[test]
+    fn test_draw_isometric() {
+        let mut generator = MazeGenerator::<3, 3, 3>::default();
+        generator.generate(Some(1));
+        let solution = solve(generator.maze(), generator.start());
+        let mut route = RoomSet::default();
+        route.insert(&generator.start());
+
+        // The mock display panics on drawing outside it, so this checks the
+        // maze is fitted to the display.
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        draw_isometric(
+            &mut display,
+            generator.maze(),
+            solution.path.as_ref(),
+            Some(&route),
+        )
+        .expect("draw");
+        assert!(display.affected_area().size.width > 48);
+    }
+
+    #[test]
+    fn test_isometric_on_one_bit() {
+        let mut generator = MazeGenerator::<3, 3, 3>::default();
+        generator.generate(Some(1));
+        let solution = solve(generator.maze(), generator.start());
+        let path = solution.path.as_ref().expect("solution");
+        let mut route = RoomSet::default();
+        for coord in path.iter() {
+            route.insert(coord);
+        }
+
+        // The shortest way out still shows inside the route where they go
+        // the same way, rather than being hidden by it.
+        let draw = |solution| {
+            let mut display = MockDisplay::<BinaryColor>::new();
+            display.set_allow_overdraw(true);
+            draw_isometric(&mut display, generator.maze(), solution, Some(&route)).expect("draw");
+            display
+        };
+        let both = draw(Some(path));
+        let route_only = draw(None);
+        assert!(route_only
+            .affected_area()
+            .points()
+            .any(|point| route_only.get_pixel(point).is_some()
+                && both.get_pixel(point) != route_only.get_pixel(point)));
+    }
+
+    #[test]
+    fn test_results_map_on_one_bit() {
+        // Four rooms in a row: the player's route only, both, the shortest
+        // way out only and neither.
+        let mut result = GameResult {
+            dimensions: (4, 1, 1),
+            ..Default::default()
+        };
+        let room = |x| Coord { x, y: 0, z: 0 };
+        result.visited.insert(&room(0));
+        result.visited.insert(&room(1));
+        result.shortest.insert(&room(1));
+        result.shortest.insert(&room(2));
+
+        let mut display = MockDisplay::<BinaryColor>::new();
+        draw_results_floor(&mut display, Point::zero(), 8, 0, &result).expect("draw");
+        let looks = |x: i32| {
+            let mut pixels = [[false; 7]; 7];
+            for (y, row) in pixels.iter_mut().enumerate() {
+                for (column, lit) in row.iter_mut().enumerate() {
+                    let point = Point::new(x * 8 + column as i32, y as i32);
+                    *lit = display.get_pixel(point) == Some(BinaryColor::On);
+                }
+            }
+            pixels
+        };
+        let rooms = [looks(0), looks(1), looks(2), looks(3)];
+        for (index, pixels) in rooms.iter().enumerate() {
+            assert!(
+                pixels.iter().flatten().any(|lit| *lit),
+                "room {} is blank",
+                index
+            );
+            assert!(
+                !rooms[..index].contains(pixels),
+                "room {} looks the same",
+                index
+            );
+        }
+    }
+}
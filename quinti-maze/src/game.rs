@@ -1,14 +1,26 @@
 use crate::{
+    algorithms::Algorithm,
     draw::{
-        draw_bottom_door, draw_front_door, draw_left_door, draw_right_door, draw_room, draw_start,
-        draw_status, draw_top_door, draw_win, update_time,
+        draw_bottom_door, draw_corridor, draw_front_door, draw_high_scores, draw_initials,
+        draw_left_door, draw_map, draw_menu, draw_out_of_time, draw_results, draw_reveal,
+        draw_right_door, draw_room, draw_status, draw_text_page, draw_top_door, update_score,
+        update_time, MapRoom, RoomDoors, Viewport, CORRIDOR_LENGTH,
     },
     maze::{
-        find_path_to_exit, Coord, Direction, MazeGenerator, QuintiMaze, SolutionPath, VisibleDoors,
+        try_distances_to_exit, try_solve, CellMap, Coord, Direction, Maze, MazeGenerator,
+        SolutionPath, VisibleDoors,
     },
+    metrics::Difficulty,
+    palette::Palette,
+    score::{GameResult, HighScore, HighScores, RoomSet, Score, Tally},
+    storage::{ByteReader, Storage, StorageKey},
 };
-use core::fmt::Debug;
-use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use core::{
+    fmt::{self, Debug},
+    mem,
+};
+use embedded_graphics::prelude::*;
+use heapless::{String, Vec};
 
 #[derive(Debug)]
 pub struct Note {
@@ -37,9 +49,226 @@ pub const NOTES: &[Note] = &[
     Note::new(1667, 653, 50),
 ];
 
+pub const BUMP_NOTES: &[Note] = &[Note::new(150, 80, 0)];
+
+pub const STEP_NOTES: &[Note] = &[Note::new(600, 30, 0)];
+
+pub const CLIMB_NOTES: &[Note] = &[Note::new(500, 40, 0), Note::new(800, 40, 10)];
+
+pub const DESCEND_NOTES: &[Note] = &[Note::new(800, 40, 0), Note::new(500, 40, 10)];
+
+pub const TURN_NOTES: &[Note] = &[Note::new(400, 20, 0)];
+
+pub const HINT_NOTES: &[Note] = &[Note::new(1333, 60, 0), Note::new(1667, 60, 20)];
+
+pub const START_NOTES: &[Note] = &[
+    Note::new(1000, 100, 0),
+    Note::new(1333, 100, 30),
+    Note::new(1667, 100, 30),
+];
+
+/// Something that happened in the game with a sound to go with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    /// Walked into a wall.
+    Bump,
+    Step,
+    Climb,
+    Descend,
+    Turn,
+    Hint,
+    Win,
+    Start,
+}
+
+impl SoundEvent {
+    /// The tune played for this event unless the platform has its own.
+    pub fn tune(&self) -> &'static [Note] {
+        match self {
+            Self::Bump => BUMP_NOTES,
+            Self::Step => STEP_NOTES,
+            Self::Climb => CLIMB_NOTES,
+            Self::Descend => DESCEND_NOTES,
+            Self::Turn => TURN_NOTES,
+            Self::Hint => HINT_NOTES,
+            Self::Win => NOTES,
+            Self::Start => START_NOTES,
+        }
+    }
+}
+
 pub trait PlatformSpecific: Debug + Default {
-    fn play_victory_notes(&mut self);
+    /// Plays the sound for `event`, unless the sound is muted.
+    fn play_sound(&mut self, event: SoundEvent);
     fn ticks(&mut self) -> u64;
+
+    /// Somewhere to keep saved games and high scores, if the platform has
+    /// one.
+    fn storage(&mut self) -> Option<&mut dyn Storage> {
+        None
+    }
+}
+
+/// How big the maze is, in rooms along each side.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MazeSize {
+    Small,
+    #[default]
+    Standard,
+    Large,
+}
+
+impl MazeSize {
+    pub const fn dimensions(&self) -> (usize, usize, usize) {
+        match self {
+            Self::Small => (3, 3, 3),
+            Self::Standard => (5, 5, 5),
+            Self::Large => (7, 7, 7),
+        }
+    }
+}
+
+/// Which moves the player makes: ones relative to the way they face, ones by
+/// the compass like the 1982 original, or either.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementScheme {
+    #[default]
+    Relative,
+    Absolute,
+    Hybrid,
+}
+
+impl MovementScheme {
+    pub const ALL: [MovementScheme; 3] = [
+        MovementScheme::Relative,
+        MovementScheme::Absolute,
+        MovementScheme::Hybrid,
+    ];
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|scheme| *scheme as u8 == index)
+    }
+
+    /// Whether `command` is a move this scheme makes. Turning and facing
+    /// about work in all of them.
+    pub fn allows(&self, command: Command) -> bool {
+        match command {
+            Command::MoveForward | Command::MoveLeft | Command::MoveRight | Command::MoveBack => {
+                *self != Self::Absolute
+            }
+            Command::Move(Direction::Up | Direction::Down) => true,
+            Command::Move(_) | Command::Face(_) => *self != Self::Relative,
+            _ => true,
+        }
+    }
+}
+
+impl From<MovementScheme> for &'static str {
+    fn from(scheme: MovementScheme) -> Self {
+        match scheme {
+            MovementScheme::Relative => "Relative",
+            MovementScheme::Absolute => "Compass",
+            MovementScheme::Hybrid => "Hybrid",
+        }
+    }
+}
+
+/// Everything chosen on the start screen before a game begins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameConfig {
+    pub difficulty: Difficulty,
+    pub size: MazeSize,
+    pub algorithm: Algorithm,
+    /// How many hints the player may ask for, or `None` for no limit.
+    pub hints: Option<u8>,
+    /// Milliseconds allowed to find the way out, or `None` for no limit.
+    pub time_limit: Option<u64>,
+    pub movement: MovementScheme,
+    /// No sounds are played.
+    pub muted: bool,
+}
+
+impl From<Difficulty> for GameConfig {
+    fn from(difficulty: Difficulty) -> Self {
+        match difficulty {
+            // Long corridors with few choices in a small maze.
+            Difficulty::Easy => Self {
+                difficulty,
+                size: MazeSize::Small,
+                algorithm: Algorithm::RecursiveBacktracker,
+                hints: None,
+                time_limit: None,
+                movement: MovementScheme::Relative,
+                muted: false,
+            },
+            // The original game.
+            Difficulty::Medium => Self {
+                difficulty,
+                size: MazeSize::Standard,
+                algorithm: Algorithm::GrowingTree,
+                hints: None,
+                time_limit: None,
+                movement: MovementScheme::Relative,
+                muted: false,
+            },
+            Difficulty::Hard => Self {
+                difficulty,
+                size: MazeSize::Large,
+                algorithm: Algorithm::Prim,
+                hints: Some(3),
+                time_limit: Some(10 * 60 * 1000),
+                movement: MovementScheme::Relative,
+                muted: false,
+            },
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Difficulty::default().into()
+    }
+}
+
+/// How many mazes to try for one of the chosen difficulty.
+const RATED_ATTEMPTS: u64 = 20;
+
+const SNAPSHOT_MAGIC: &[u8] = b"QMZ";
+pub const SNAPSHOT_VERSION: u8 = 1;
+pub const SNAPSHOT_MAX_LEN: usize = 64
+    + Maze::<7, 7, 7>::byte_len()
+    + Maze::<7, 7, 7>::cell_count()
+    + visited_len(Maze::<7, 7, 7>::cell_count());
+const SNAPSHOT_UNLIMITED_HINTS: u8 = u8::MAX;
+const SNAPSHOT_NO_EXIT: u8 = u8::MAX;
+
+/// A game in progress, saved in a versioned binary format.
+pub type Snapshot = Vec<u8, SNAPSHOT_MAX_LEN>;
+
+/// Bytes needed to save which of `cell_count` rooms were visited.
+const fn visited_len(cell_count: usize) -> usize {
+    cell_count.div_ceil(8)
+}
+
+/// Every `Direction` bit, for a room whose sides have all been seen.
+const ALL_SIDES: u8 = (1 << Direction::ALL.len()) - 1;
+
+const SNAPSHOT_SHOW_POSITION: u8 = 1;
+const SNAPSHOT_SHOW_HINT: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveError {
+    NotPlaying,
+    NoStorage,
+    WriteFailed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreError {
+    NoStorage,
+    NotFound,
+    Corrupt,
+    UnsupportedVersion(u8),
 }
 
 #[derive(Debug, PartialEq)]
@@ -55,7 +284,7 @@ impl Default for RedrawMode {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Command {
     MoveForward,
     MoveLeft,
@@ -66,97 +295,545 @@ pub enum Command {
     TurnRight,
     ToggleShowPosition,
     ShowHints,
+    ShowMap,
+    /// Steps back through the door behind, still facing the same way.
+    MoveBack,
+    /// Moves by the compass, whichever way the player faces.
+    Move(Direction),
+    /// Turns to face north, south, east or west.
+    Face(Direction),
+    AboutFace,
+    /// Saves the game and goes back to the menu, or back from a menu page.
+    Quit,
 }
 
-#[derive(Debug, Default)]
-struct Showing {
-    pub left: bool,
-    pub front: bool,
-    pub right: bool,
-    pub top: bool,
-    pub bottom: bool,
+impl Command {
+    /// The same move made relative to `facing`, for a compass move.
+    pub fn relative_to(self, facing: Direction) -> Self {
+        match self {
+            Self::Move(Direction::Up) => Self::MoveUp,
+            Self::Move(Direction::Down) => Self::MoveDown,
+            Self::Move(direction) if direction == facing => Self::MoveForward,
+            Self::Move(direction) if direction == facing.opposite() => Self::MoveBack,
+            Self::Move(direction) if direction == VisibleDoors::Left.direction(facing) => {
+                Self::MoveLeft
+            }
+            Self::Move(_) => Self::MoveRight,
+            command => command,
+        }
+    }
+}
+
+/// A key as the frontend sees it: what it's bound to and what it types.
+/// Keys with neither still count as any key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub command: Option<Command>,
+    /// The character typed, on keyboards that can type.
+    pub character: Option<char>,
+}
+
+impl From<Command> for Key {
+    fn from(command: Command) -> Self {
+        Self {
+            command: Some(command),
+            character: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    KeyDown(Key),
+    KeyUp(Key),
+    /// A key held down long enough to count as a long press. While playing,
+    /// a long press of a key without a command saves the game if it can and
+    /// goes back to the menu. Other keys already did their command when they
+    /// went down.
+    LongPress(Key),
+    /// A key repeating while it's held down. It does what the key does, but
+    /// doesn't count as any key on the screens waiting for one.
+    Repeat(Key),
+}
+
+/// What came of an `Input`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputOutcome {
+    /// The input did something.
+    pub handled: bool,
+    /// The next `draw` has more to draw than the clock.
+    pub redraw: bool,
+    /// The input took the player out of the maze.
+    pub won: bool,
 }
 
 #[derive(Debug, Default)]
-struct PlayingPhaseData {
-    maze: QuintiMaze,
+struct PlayingPhaseData<const X: usize, const Y: usize, const Z: usize> {
+    maze: Maze<X, Y, Z>,
     position: Coord,
+    /// The room the player started in.
+    entrance: Coord,
+    visited: RoomSet,
+    /// The sides of each room the player has looked at, as `Direction` bits.
+    seen: CellMap<u8, X, Y, Z>,
+    /// The floor shown on the map, while it's showing.
+    map_floor: Option<isize>,
     next_redraw: RedrawMode,
     show_position: bool,
     direction_hint: Option<Direction>,
-    path_to_exit: Option<SolutionPath>,
+    path_to_exit: Option<SolutionPath<X, Y, Z>>,
     facing: Direction,
+    movement: MovementScheme,
+    /// The sound for the last command, until the game plays it.
+    sound: Option<SoundEvent>,
     start: u64,
-    showing: Showing,
+    earlier_elapsed: u64,
+    difficulty: Difficulty,
+    hints_left: Option<u8>,
+    time_limit: Option<u64>,
+    tally: Tally,
+    par: u32,
+    showing: RoomDoors,
+    /// The rooms drawn through the front door.
+    showing_corridor: Vec<RoomDoors, CORRIDOR_LENGTH>,
 }
 
-impl PlayingPhaseData {
-    pub fn new(ticks: u64) -> Self {
-        let mut generator = MazeGenerator::default();
-        generator.generate(Some(ticks));
-        Self {
-            maze: generator.take(),
-            #[cfg(feature = "easy")]
-            position: Coord { x: 4, y: 4, z: 4 },
+impl<const X: usize, const Y: usize, const Z: usize> PlayingPhaseData<X, Y, Z> {
+    pub fn new(ticks: u64, config: &GameConfig) -> Self {
+        // Falls back to the last maze tried when none rate as chosen.
+        let mut generator = MazeGenerator::<X, Y, Z>::default();
+        generator
+            .generate_rated(config.algorithm, config.difficulty, ticks, RATED_ATTEMPTS)
+            .expect("maze generation");
+        let position = generator.start();
+        let maze = generator.take();
+        let mut visited = RoomSet::default();
+        visited.insert(&position);
+        let mut playing = Self {
+            par: Self::par(&maze, position),
+            position,
+            entrance: position,
+            visited,
+            maze,
+            start: ticks,
+            difficulty: config.difficulty,
+            hints_left: config.hints,
+            time_limit: config.time_limit,
+            movement: config.movement,
+            ..Default::default()
+        };
+        playing.look_around();
+        playing
+    }
+
+    /// Moves on the shortest way out from `position`.
+    fn par(maze: &Maze<X, Y, Z>, position: Coord) -> u32 {
+        try_distances_to_exit(maze)
+            .ok()
+            .and_then(|distances| distances[&position])
+            .unwrap_or_default() as u32
+    }
+
+    pub fn score(&self, ticks: u64) -> Score {
+        Score {
+            tally: self.tally,
+            par: self.par,
+            elapsed: self.elapsed(ticks),
+        }
+    }
+
+    /// How the game went, with the rooms visited and those on the shortest
+    /// way out from the entrance.
+    pub fn result(&self, ticks: u64) -> GameResult {
+        let mut shortest = RoomSet::default();
+        if let Some(path) = try_solve(&self.maze, self.entrance)
+            .ok()
+            .and_then(|solution| solution.path)
+        {
+            for coord in path.iter() {
+                if !self.maze.is_win(coord) {
+                    shortest.insert(coord);
+                }
+            }
+        }
+        GameResult {
+            score: self.score(ticks),
+            difficulty: self.difficulty,
+            dimensions: Maze::<X, Y, Z>::dimensions(),
+            visited: self.visited,
+            shortest,
+        }
+    }
+
+    /// Time spent playing, including time before the game was saved.
+    fn elapsed(&self, ticks: u64) -> u64 {
+        self.earlier_elapsed + ticks - self.start
+    }
+
+    /// The time for the status bar, which counts down when there's a limit.
+    fn shown_time(&self, ticks: u64) -> u64 {
+        let elapsed = self.elapsed(ticks);
+        match self.time_limit {
+            Some(limit) => limit.saturating_sub(elapsed),
+            None => elapsed,
+        }
+    }
+
+    pub fn is_out_of_time(&self, ticks: u64) -> bool {
+        self.time_limit
+            .is_some_and(|limit| self.elapsed(ticks) >= limit)
+    }
+
+    pub fn snapshot(&self, ticks: u64) -> Snapshot {
+        let (x, y, z) = Maze::<X, Y, Z>::dimensions();
+        let mut flags = 0;
+        if self.show_position {
+            flags |= SNAPSHOT_SHOW_POSITION;
+        }
+        if self.direction_hint.is_some() {
+            flags |= SNAPSHOT_SHOW_HINT;
+        }
+
+        let mut snapshot = Snapshot::new();
+        let mut write = |bytes: &[u8]| snapshot.extend_from_slice(bytes).expect("snapshot");
+        write(SNAPSHOT_MAGIC);
+        write(&[SNAPSHOT_VERSION, x as u8, y as u8, z as u8]);
+        write(self.maze.as_bytes());
+        match self.maze.exit() {
+            Some((exit, direction)) => {
+                write(&[exit.x as u8, exit.y as u8, exit.z as u8, direction as u8])
+            }
+            None => write(&[0, 0, 0, SNAPSHOT_NO_EXIT]),
+        }
+        write(&[
+            self.position.x as u8,
+            self.position.y as u8,
+            self.position.z as u8,
+            self.facing as u8,
+        ]);
+        write(&self.elapsed(ticks).to_le_bytes());
+        write(&[
+            flags,
+            self.difficulty as u8,
+            self.hints_left.unwrap_or(SNAPSHOT_UNLIMITED_HINTS),
+        ]);
+        write(&self.time_limit.unwrap_or_default().to_le_bytes());
+        for count in [
+            self.tally.moves,
+            self.tally.bumps,
+            self.tally.turns,
+            self.tally.hints,
+            self.par,
+        ] {
+            write(&count.to_le_bytes());
+        }
+        write(&[
+            self.entrance.x as u8,
+            self.entrance.y as u8,
+            self.entrance.z as u8,
+        ]);
+        let mut visited = [0; visited_len(Maze::<7, 7, 7>::cell_count())];
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            if self.visited.contains(&Maze::<X, Y, Z>::coord_at(index)) {
+                visited[index / 8] |= 1 << (index % 8);
+            }
+        }
+        write(&visited[..visited_len(Maze::<X, Y, Z>::cell_count())]);
+        write(self.seen.as_slice());
+        write(&[self.movement as u8]);
+        snapshot
+    }
+
+    pub fn from_snapshot(data: &[u8], ticks: u64) -> Result<Self, RestoreError> {
+        let mut reader = ByteReader::new(data);
+        if reader.bytes(SNAPSHOT_MAGIC.len()) != Some(SNAPSHOT_MAGIC) {
+            return Err(RestoreError::Corrupt);
+        }
+        let version = reader.u8().ok_or(RestoreError::Corrupt)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(RestoreError::UnsupportedVersion(version));
+        }
+
+        let (x, y, z) = Maze::<X, Y, Z>::dimensions();
+        if reader.bytes(3) != Some(&[x as u8, y as u8, z as u8]) {
+            return Err(RestoreError::Corrupt);
+        }
+        let walls = reader
+            .bytes(Maze::<X, Y, Z>::byte_len())
+            .ok_or(RestoreError::Corrupt)?;
+        let exit = Self::read_coord(&mut reader)?;
+        let exit_direction = reader.u8().ok_or(RestoreError::Corrupt)?;
+        let exit = match exit_direction {
+            SNAPSHOT_NO_EXIT => None,
+            direction => Some((
+                exit,
+                Direction::from_index(direction).ok_or(RestoreError::Corrupt)?,
+            )),
+        };
+        let maze = Maze::from_bytes(walls, exit).ok_or(RestoreError::Corrupt)?;
+        let position = Self::read_coord(&mut reader)?;
+        let facing = reader
+            .u8()
+            .and_then(Direction::from_index)
+            .filter(|facing| !matches!(facing, Direction::Up | Direction::Down))
+            .ok_or(RestoreError::Corrupt)?;
+        let earlier_elapsed = reader.u64().ok_or(RestoreError::Corrupt)?;
+        let flags = reader.u8().ok_or(RestoreError::Corrupt)?;
+
+        let difficulty = reader
+            .u8()
+            .and_then(Difficulty::from_index)
+            .ok_or(RestoreError::Corrupt)?;
+        let hints_left = reader
+            .u8()
+            .map(|hints| (hints != SNAPSHOT_UNLIMITED_HINTS).then_some(hints))
+            .ok_or(RestoreError::Corrupt)?;
+        let time_limit = reader
+            .u64()
+            .map(|limit| (limit != 0).then_some(limit))
+            .ok_or(RestoreError::Corrupt)?;
+
+        let mut count = || reader.u32().ok_or(RestoreError::Corrupt);
+        let tally = Tally {
+            moves: count()?,
+            bumps: count()?,
+            turns: count()?,
+            hints: count()?,
+        };
+        let par = count()?;
+
+        let entrance = Self::read_coord(&mut reader)?;
+        let mut visited = RoomSet::default();
+        let bits = reader
+            .bytes(visited_len(Maze::<X, Y, Z>::cell_count()))
+            .ok_or(RestoreError::Corrupt)?;
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            if bits[index / 8] & 1 << (index % 8) != 0 {
+                visited.insert(&Maze::<X, Y, Z>::coord_at(index));
+            }
+        }
+
+        let mut seen = CellMap::<u8, X, Y, Z>::default();
+        let bytes = reader
+            .bytes(Maze::<X, Y, Z>::cell_count())
+            .ok_or(RestoreError::Corrupt)?;
+        if bytes.iter().any(|sides| sides & !ALL_SIDES != 0) {
+            return Err(RestoreError::Corrupt);
+        }
+        seen.as_mut_slice().copy_from_slice(bytes);
+
+        let movement = reader
+            .u8()
+            .and_then(MovementScheme::from_index)
+            .ok_or(RestoreError::Corrupt)?;
+        if !reader.is_empty() || maze.is_win(&position) || maze.is_win(&entrance) {
+            return Err(RestoreError::Corrupt);
+        }
+
+        let mut playing = Self {
+            maze,
+            position,
+            entrance,
+            visited,
+            seen,
+            facing,
+            show_position: flags & SNAPSHOT_SHOW_POSITION != 0,
             start: ticks,
+            earlier_elapsed,
+            difficulty,
+            hints_left,
+            time_limit,
+            movement,
+            tally,
+            par,
+            ..Default::default()
+        };
+        if flags & SNAPSHOT_SHOW_HINT != 0 {
+            playing.find_direction_hint();
+        }
+        playing.look_around();
+        Ok(playing)
+    }
+
+    fn read_coord(reader: &mut ByteReader<'_>) -> Result<Coord, RestoreError> {
+        let mut coordinate = || reader.i8().map(isize::from).ok_or(RestoreError::Corrupt);
+        Ok(Coord {
+            x: coordinate()?,
+            y: coordinate()?,
+            z: coordinate()?,
+        })
+    }
+
+    fn room_doors(&self, coord: &Coord) -> RoomDoors {
+        let cell = self.maze.get_cell(coord);
+        RoomDoors {
+            left: cell.left(self.facing),
+            front: cell.front(self.facing),
+            right: cell.right(self.facing),
+            top: cell.top(),
+            bottom: cell.bottom(),
+        }
+    }
+
+    /// The rooms in sight through open front doors, nearest first.
+    fn corridor(&self) -> Vec<RoomDoors, CORRIDOR_LENGTH> {
+        let mut rooms = Vec::new();
+        let mut coord = self.position;
+        while self.maze.get_cell(&coord).has_door(self.facing) && !rooms.is_full() {
+            coord = coord.move_in_direction(self.facing);
+            if self.maze.is_win(&coord) {
+                break;
+            }
+            rooms.push(self.room_doors(&coord)).ok();
+        }
+        rooms
+    }
+
+    /// Notes the sides of the room in view, for the map.
+    fn look_around(&mut self) {
+        if self.maze.is_win(&self.position) {
+            return;
+        }
+        for door in VisibleDoors::ALL {
+            self.seen[&self.position] |= 1 << door.direction(self.facing) as u8;
+        }
+    }
+
+    /// What the player knows about the room at `coord`. A side seen from
+    /// the room next door counts as seen.
+    fn map_room(&self, coord: &Coord) -> MapRoom {
+        let cell = self.maze.get_cell(coord);
+        let mut room = MapRoom {
+            visited: self.visited.contains(coord),
             ..Default::default()
+        };
+        for direction in Direction::ALL {
+            let bit = 1 << direction as u8;
+            let seen = self.seen[coord] & bit != 0
+                || Maze::<X, Y, Z>::neighbor(*coord, direction).is_some_and(|neighbor| {
+                    self.seen[&neighbor] & 1 << direction.opposite() as u8 != 0
+                });
+            if seen {
+                room.seen |= bit;
+                if cell.has_door(direction) {
+                    room.doors |= bit;
+                }
+            }
         }
+        room
     }
 
-    pub fn draw_playing<D>(&mut self, ticks: u64, display: &mut D) -> Result<(), D::Error>
+    fn show_map(&mut self, floor: isize) {
+        self.map_floor = Some(floor.clamp(0, Z as isize - 1));
+        self.next_redraw = RedrawMode::Full;
+    }
+
+    /// Draws the whole maze, with the rooms visited and the shortest way out
+    /// from the entrance.
+    fn draw_reveal<D>(&self, viewport: &Viewport, display: &mut D) -> Result<(), D::Error>
     where
-        D: DrawTarget<Color = Rgb565>,
+        D: DrawTarget,
+        D::Color: Palette,
+    {
+        let solution = try_solve(&self.maze, self.entrance).ok();
+        let path = solution
+            .as_ref()
+            .and_then(|solution| solution.path.as_ref());
+        draw_reveal(display, viewport, &self.maze, path, &self.visited)
+    }
+
+    fn draw_map<D>(
+        &mut self,
+        floor: isize,
+        viewport: &Viewport,
+        display: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: Palette,
     {
         if self.next_redraw == RedrawMode::Full {
-            self.showing = Default::default();
-            display.clear(Rgb565::WHITE)?;
-            draw_room(display)?;
+            viewport.clear(display, D::Color::BACKGROUND)?;
+            let player = (self.position.z == floor).then_some(self.position);
+            draw_map(
+                display,
+                viewport,
+                floor as usize,
+                Maze::<X, Y, Z>::dimensions(),
+                |x, y| {
+                    self.map_room(&Coord {
+                        x: x as isize,
+                        y: y as isize,
+                        z: floor,
+                    })
+                },
+                player.map(|position| (position.x as usize, position.y as usize, self.facing)),
+            )?;
         }
+        self.next_redraw = RedrawMode::Time;
+        Ok(())
+    }
 
-        let cell = self.maze.get_cell(&self.position);
-
-        let showing_right = cell.right(self.facing);
-        if showing_right != self.showing.right {
-            draw_right_door(display, showing_right)?;
-            self.showing.right = showing_right;
+    pub fn draw_playing<D>(
+        &mut self,
+        ticks: u64,
+        viewport: &Viewport,
+        display: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: Palette,
+    {
+        if let Some(floor) = self.map_floor {
+            return self.draw_map(floor, viewport, display);
         }
 
-        let showing_left = cell.left(self.facing);
-        if showing_left != self.showing.left {
-            draw_left_door(display, showing_left)?;
-            self.showing.left = showing_left;
+        if self.next_redraw == RedrawMode::Full {
+            self.showing = Default::default();
+            self.showing_corridor.clear();
+            viewport.clear(display, D::Color::ROOM)?;
+            draw_room(display, viewport)?;
         }
 
-        let showing_top = cell.top();
-        if showing_top != self.showing.top {
-            draw_top_door(display, showing_top)?;
-            self.showing.top = showing_top;
-        }
+        let doors = self.room_doors(&self.position);
 
-        let showing_bottom = cell.bottom();
-        if showing_bottom != self.showing.bottom {
-            draw_bottom_door(display, showing_bottom)?;
-            self.showing.bottom = showing_bottom;
+        if doors.right != self.showing.right {
+            draw_right_door(display, viewport, doors.right)?;
+        }
+        if doors.left != self.showing.left {
+            draw_left_door(display, viewport, doors.left)?;
+        }
+        if doors.top != self.showing.top {
+            draw_top_door(display, viewport, doors.top)?;
+        }
+        if doors.bottom != self.showing.bottom {
+            draw_bottom_door(display, viewport, doors.bottom)?;
         }
 
-        let showing_front = cell.front(self.facing);
-        if showing_front != self.showing.front {
-            draw_front_door(display, showing_front)?;
-            self.showing.front = showing_front;
+        let corridor = self.corridor();
+        if doors.front != self.showing.front || corridor != self.showing_corridor {
+            draw_front_door(display, viewport, doors.front)?;
+            draw_corridor(display, viewport, &corridor)?;
+            self.showing_corridor = corridor;
         }
+        self.showing = doors;
 
-        let elapsed = ticks - self.start;
+        let time = self.shown_time(ticks);
+        let score = self.score(ticks);
 
         if self.next_redraw != RedrawMode::Time {
             draw_status(
                 display,
+                viewport,
                 self.facing,
                 self.show_position.then_some(self.position),
-                self.direction_hint,
-                elapsed,
+                self.hint_label(),
+                time,
+                &score,
             )?;
         } else {
-            update_time(display, elapsed)?;
+            update_time(display, viewport, time)?;
+            update_score(display, viewport, &score)?;
         }
         self.next_redraw = RedrawMode::Time;
 
@@ -164,14 +841,32 @@ impl PlayingPhaseData {
     }
 
     pub fn try_move(&mut self, door: VisibleDoors) -> bool {
+        self.try_move_in(door.direction(self.facing))
+    }
+
+    /// Moves through the door in `direction`, if there is one, returning
+    /// whether that's the way out.
+    pub fn try_move_in(&mut self, direction: Direction) -> bool {
         let cell = self.maze.get_cell(&self.position);
 
         let old_position = self.position;
 
-        let direction = door.direction(self.facing);
-
         if cell.has_door(direction) {
+            self.sound = Some(match direction {
+                Direction::Up => SoundEvent::Climb,
+                Direction::Down => SoundEvent::Descend,
+                _ => SoundEvent::Step,
+            });
             self.position = self.position.move_in_direction(direction);
+            if !self.maze.is_win(&self.position) {
+                self.visited.insert(&self.position);
+            }
+            self.look_around();
+            self.tally.moves += 1;
+        } else {
+            self.sound = Some(SoundEvent::Bump);
+            self.tally.bumps += 1;
+            self.next_redraw = RedrawMode::Status;
         }
 
         if self.position != old_position {
@@ -182,7 +877,7 @@ impl PlayingPhaseData {
                 let on_path = path_to_exit.pop_back();
                 if on_path == Some(self.position) {
                     if let Some(next_position) = path_to_exit.back() {
-                        self.direction_hint = Some(self.position.direction_to(*next_position));
+                        self.direction_hint = self.position.try_direction_to(*next_position).ok();
                         self.path_to_exit = Some(path_to_exit);
                     }
                 }
@@ -194,31 +889,103 @@ impl PlayingPhaseData {
 
     pub fn turn_left(&mut self) {
         self.facing = VisibleDoors::Left.direction(self.facing);
+        self.look_around();
+        self.sound = Some(SoundEvent::Turn);
+        self.tally.turns += 1;
         self.next_redraw = RedrawMode::Status;
     }
 
     pub fn turn_right(&mut self) {
         self.facing = VisibleDoors::Right.direction(self.facing);
+        self.look_around();
+        self.sound = Some(SoundEvent::Turn);
+        self.tally.turns += 1;
+        self.next_redraw = RedrawMode::Status;
+    }
+
+    /// Turns to face `direction`, counting a turn if it's a new way.
+    pub fn face(&mut self, direction: Direction) {
+        if matches!(direction, Direction::Up | Direction::Down) || direction == self.facing {
+            return;
+        }
+        self.facing = direction;
+        self.look_around();
+        self.sound = Some(SoundEvent::Turn);
+        self.tally.turns += 1;
         self.next_redraw = RedrawMode::Status;
     }
 
+    /// The hint as it's shown: which way to move in the player's scheme.
+    fn hint_label(&self) -> Option<&'static str> {
+        let hint = self.direction_hint?;
+        if self.movement != MovementScheme::Relative {
+            return Some(hint.into());
+        }
+        Some(match Command::Move(hint).relative_to(self.facing) {
+            Command::MoveForward => "Ahead",
+            Command::MoveBack => "Behind",
+            Command::MoveLeft => "Left",
+            Command::MoveRight => "Right",
+            _ => hint.into(),
+        })
+    }
+
     pub fn toggle_show_position(&mut self) {
         self.show_position = !self.show_position;
         self.next_redraw = RedrawMode::Status;
     }
 
+    /// Shows the way to the exit, using up one of the player's hints unless
+    /// one is already showing.
     pub fn show_direction_hint(&mut self) {
-        let (_found, mut path) = find_path_to_exit(&self.maze, self.position);
-        path.pop_back();
-        let next_position = path.back();
-        if let Some(next_position) = next_position {
-            self.direction_hint = Some(self.position.direction_to(*next_position));
-            self.path_to_exit = Some(path);
+        if self.direction_hint.is_some() {
+            return;
+        }
+        if let Some(hints_left) = &mut self.hints_left {
+            match hints_left.checked_sub(1) {
+                Some(remaining) => *hints_left = remaining,
+                None => return,
+            }
+        }
+        self.find_direction_hint();
+        if self.direction_hint.is_some() {
+            self.sound = Some(SoundEvent::Hint);
+            self.tally.hints += 1;
+        }
+    }
+
+    fn find_direction_hint(&mut self) {
+        if let Some(mut path) = try_solve(&self.maze, self.position)
+            .ok()
+            .and_then(|solution| solution.path)
+        {
+            path.pop_back();
+            if let Some(next_position) = path.back() {
+                self.direction_hint = self.position.try_direction_to(*next_position).ok();
+                self.path_to_exit = Some(path);
+            }
         }
         self.next_redraw = RedrawMode::Status;
     }
 
     pub fn handle_command(&mut self, command: Command) -> bool {
+        // On the map, up and down change floor and anything else goes back.
+        if let Some(floor) = self.map_floor {
+            match command.relative_to(self.facing) {
+                Command::MoveUp => self.show_map(floor + 1),
+                Command::MoveDown => self.show_map(floor - 1),
+                _ => {
+                    self.map_floor = None;
+                    self.next_redraw = RedrawMode::Full;
+                }
+            }
+            return false;
+        }
+
+        if !self.movement.allows(command) {
+            return false;
+        }
+
         let mut is_win = false;
         match command {
             Command::MoveForward => {
@@ -236,6 +1003,18 @@ impl PlayingPhaseData {
             Command::MoveDown => {
                 is_win = self.try_move(VisibleDoors::Down);
             }
+            Command::MoveBack => {
+                is_win = self.try_move_in(self.facing.opposite());
+            }
+            Command::Move(direction) => {
+                is_win = self.try_move_in(direction);
+            }
+            Command::Face(direction) => {
+                self.face(direction);
+            }
+            Command::AboutFace => {
+                self.face(self.facing.opposite());
+            }
             Command::TurnLeft => {
                 self.turn_left();
             }
@@ -248,6 +1027,11 @@ impl PlayingPhaseData {
             Command::ShowHints => {
                 self.show_direction_hint();
             }
+            Command::ShowMap => {
+                self.show_map(self.position.z);
+            }
+            // The game takes this to leave the maze.
+            Command::Quit => (),
         }
 
         is_win
@@ -258,23 +1042,293 @@ impl PlayingPhaseData {
     }
 }
 
+/// A game in progress, in whichever size of maze was chosen.
+enum Playing {
+    Small(PlayingPhaseData<3, 3, 3>),
+    Standard(PlayingPhaseData<5, 5, 5>),
+    Large(PlayingPhaseData<7, 7, 7>),
+}
+
+/// Runs `$body` with `$data` bound to the `PlayingPhaseData` inside
+/// `$playing`, whatever its size.
+macro_rules! with_playing {
+    ($playing:expr, $data:ident => $body:expr) => {
+        match $playing {
+            Playing::Small($data) => $body,
+            Playing::Standard($data) => $body,
+            Playing::Large($data) => $body,
+        }
+    };
+}
+
+impl Playing {
+    fn new(ticks: u64, config: &GameConfig) -> Self {
+        match config.size {
+            MazeSize::Small => Self::Small(PlayingPhaseData::new(ticks, config)),
+            MazeSize::Standard => Self::Standard(PlayingPhaseData::new(ticks, config)),
+            MazeSize::Large => Self::Large(PlayingPhaseData::new(ticks, config)),
+        }
+    }
+
+    fn from_snapshot(data: &[u8], ticks: u64) -> Result<Self, RestoreError> {
+        let dimensions = data
+            .get(SNAPSHOT_MAGIC.len() + 1..SNAPSHOT_MAGIC.len() + 4)
+            .ok_or(RestoreError::Corrupt)?;
+        let size = [MazeSize::Small, MazeSize::Standard, MazeSize::Large]
+            .into_iter()
+            .find(|size| {
+                let (x, y, z) = size.dimensions();
+                dimensions == [x as u8, y as u8, z as u8]
+            })
+            .ok_or(RestoreError::Corrupt)?;
+        Ok(match size {
+            MazeSize::Small => Self::Small(PlayingPhaseData::from_snapshot(data, ticks)?),
+            MazeSize::Standard => Self::Standard(PlayingPhaseData::from_snapshot(data, ticks)?),
+            MazeSize::Large => Self::Large(PlayingPhaseData::from_snapshot(data, ticks)?),
+        })
+    }
+}
+
+/// The choices on the start menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    NewGame,
+    Continue,
+    Settings,
+    HighScores,
+    Instructions,
+    About,
+}
+
+impl MenuItem {
+    pub const ALL: [MenuItem; 6] = [
+        MenuItem::NewGame,
+        MenuItem::Continue,
+        MenuItem::Settings,
+        MenuItem::HighScores,
+        MenuItem::Instructions,
+        MenuItem::About,
+    ];
+}
+
+impl From<MenuItem> for &'static str {
+    fn from(item: MenuItem) -> Self {
+        match item {
+            MenuItem::NewGame => "New Game",
+            MenuItem::Continue => "Continue",
+            MenuItem::Settings => "Settings",
+            MenuItem::HighScores => "High Scores",
+            MenuItem::Instructions => "Instructions",
+            MenuItem::About => "About",
+        }
+    }
+}
+
+/// What the menu is showing, either the list of `MenuItem`s or one of the
+/// screens reached from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuScreen {
+    Main,
+    Settings,
+    HighScores,
+    Instructions,
+    About,
+}
+
+const SETTINGS_DIFFICULTY: usize = 0;
+const SETTINGS_MOVEMENT: usize = 1;
+const SETTINGS_SOUND: usize = 2;
+const SETTINGS_BACK: usize = 3;
+const SETTINGS_COUNT: usize = 4;
+
+/// Adapted from lines 2010-2060 of `quinti-maze.bas`.
+const INSTRUCTIONS: &[&str] = &[
+    "The object of Maze is to find your way",
+    "out of a cubic maze. In one of the",
+    "rooms there is an exit out of the maze.",
+    "You must try to find it in as few turns",
+    "as possible.",
+    "",
+    "Move forward, left, right, up or down",
+    "through the open doors, and turn left",
+    "or right to change facing. Position",
+    "shows where you are, a hint points the",
+    "way out, and the map shows each floor.",
+];
+
+const ABOUT: &[&str] = &[
+    "Quinti-Maze was published in Byte",
+    "Magazine in September 1982, written in",
+    "Applesoft BASIC for the Apple II.",
+    "",
+    "Forty years later it was rewritten in",
+    "embedded Rust for the Adafruit Feather",
+    "M4, this time with a real maze",
+    "generator.",
+];
+
+#[derive(Debug)]
+struct Menu {
+    screen: MenuScreen,
+    selected: usize,
+    can_continue: bool,
+    /// The place on the high score table to pick out, after one is entered.
+    highlight: Option<usize>,
+    drawn: bool,
+}
+
+impl Menu {
+    fn new(can_continue: bool) -> Self {
+        Self {
+            screen: MenuScreen::Main,
+            selected: 0,
+            can_continue,
+            highlight: None,
+            drawn: false,
+        }
+    }
+
+    fn item_count(&self) -> usize {
+        match self.screen {
+            MenuScreen::Main => MenuItem::ALL.len(),
+            MenuScreen::Settings => SETTINGS_COUNT,
+            MenuScreen::HighScores => Difficulty::ALL.len(),
+            _ => 0,
+        }
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        let count = self.item_count();
+        if count > 0 {
+            let step = if forward { 1 } else { count - 1 };
+            self.selected = (self.selected + step) % count;
+            self.highlight = None;
+            self.drawn = false;
+        }
+    }
+
+    fn show(&mut self, screen: MenuScreen, selected: usize) {
+        self.screen = screen;
+        self.selected = selected;
+        self.highlight = None;
+        self.drawn = false;
+    }
+
+    fn draw<D>(
+        &self,
+        config: &GameConfig,
+        high_scores: &HighScores,
+        viewport: &Viewport,
+        display: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: Palette,
+    {
+        viewport.clear(display, D::Color::BACKGROUND)?;
+        match self.screen {
+            MenuScreen::Main => {
+                let mut items = [("", true); MenuItem::ALL.len()];
+                for (entry, item) in items.iter_mut().zip(MenuItem::ALL) {
+                    *entry = (item.into(), item != MenuItem::Continue || self.can_continue);
+                }
+                draw_menu(display, viewport, "Quinti-Maze", &items, self.selected)
+            }
+            MenuScreen::Settings => {
+                let mut difficulty = String::<32>::new();
+                let name: &str = config.difficulty.into();
+                fmt::write(&mut difficulty, format_args!("Difficulty: {}", name)).expect("write");
+                let mut movement = String::<32>::new();
+                let name: &str = config.movement.into();
+                fmt::write(&mut movement, format_args!("Movement: {}", name)).expect("write");
+                let sound = if config.muted {
+                    "Sound: Off"
+                } else {
+                    "Sound: On"
+                };
+                let items = [
+                    (difficulty.as_str(), true),
+                    (movement.as_str(), true),
+                    (sound, true),
+                    ("Back", true),
+                ];
+                draw_menu(display, viewport, "Settings", &items, self.selected)
+            }
+            MenuScreen::HighScores => {
+                let difficulty = Difficulty::ALL[self.selected];
+                let table = high_scores.table(difficulty);
+                draw_high_scores(display, viewport, difficulty.into(), table, self.highlight)
+            }
+            MenuScreen::Instructions => {
+                draw_text_page(display, viewport, "Instructions", INSTRUCTIONS)
+            }
+            MenuScreen::About => draw_text_page(display, viewport, "About", ABOUT),
+        }
+    }
+}
+
 enum Phase {
-    Start(bool),
-    Playing(PlayingPhaseData),
-    Done(bool),
+    Menu(Menu),
+    Playing(Playing),
+    Done(Finished),
+    EnterInitials(InitialsEntry),
+    OutOfTime(bool),
+}
+
+/// A won game, kept to show the results and then the whole maze.
+struct Finished {
+    result: GameResult,
+    playing: Playing,
+    revealing: bool,
+    drawn: bool,
+}
+
+/// Typing in initials for a score that made the high score table.
+#[derive(Debug)]
+struct InitialsEntry {
+    difficulty: Difficulty,
+    score: Score,
+    place: usize,
+    initials: [u8; 3],
+    cursor: usize,
+    drawn: bool,
+}
+
+impl InitialsEntry {
+    fn change_letter(&mut self, forward: bool) {
+        let letter = &mut self.initials[self.cursor];
+        let offset = if forward { 1 } else { 25 };
+        *letter = b'A' + (*letter - b'A' + offset) % 26;
+        self.drawn = false;
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        self.cursor = if forward {
+            (self.cursor + 1).min(self.initials.len() - 1)
+        } else {
+            self.cursor.saturating_sub(1)
+        };
+        self.drawn = false;
+    }
+
+    fn is_on_last(&self) -> bool {
+        self.cursor == self.initials.len() - 1
+    }
 }
 
 pub struct Game<T: PlatformSpecific> {
     platform: T,
     phase: Phase,
+    config: GameConfig,
+    high_scores: HighScores,
+    /// The initials last put on the high score table, offered next time.
+    initials: [u8; 3],
+    viewport: Viewport,
 }
 
 impl<T: PlatformSpecific> Default for Game<T> {
     fn default() -> Self {
-        Self {
-            platform: Default::default(),
-            phase: Phase::Start(false),
-        }
+        Self::with_platform(Default::default())
     }
 }
 
@@ -283,34 +1337,160 @@ impl<T: PlatformSpecific> Game<T> {
         Default::default()
     }
 
-    pub fn draw_start<D>(&self, display: &mut D) -> Result<(), D::Error>
-    where
-        D: DrawTarget<Color = Rgb565>,
-    {
-        display.clear(Rgb565::BLACK)?;
-        draw_start(display)?;
+    pub fn with_platform(mut platform: T) -> Self {
+        let high_scores = platform.storage().map(HighScores::load).unwrap_or_default();
+        Self {
+            platform,
+            phase: Phase::Menu(Menu::new(false)),
+            config: Default::default(),
+            high_scores,
+            initials: *b"AAA",
+            viewport: Default::default(),
+        }
+    }
+
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// The settings the next game will start with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.config = config;
+        if let Phase::Menu(menu) = &mut self.phase {
+            menu.drawn = false;
+        }
+    }
+
+    /// The platform, for frontends that need it between calls, like to
+    /// play sounds.
+    pub fn platform_mut(&mut self) -> &mut T {
+        &mut self.platform
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Moves the game to another part of the display, or resizes it, and
+    /// redraws it there on the next `draw`.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+        self.redraw();
+    }
+
+    /// Has the next `draw` draw everything again.
+    fn redraw(&mut self) {
+        match &mut self.phase {
+            Phase::Playing(playing) => {
+                with_playing!(playing, data => data.next_redraw = RedrawMode::Full)
+            }
+            Phase::OutOfTime(drawn) => *drawn = false,
+            Phase::Done(finished) => finished.drawn = false,
+            Phase::EnterInitials(entry) => entry.drawn = false,
+            Phase::Menu(menu) => menu.drawn = false,
+        }
+    }
+
+    /// The game in progress, if there is one.
+    pub fn snapshot(&mut self) -> Option<Snapshot> {
+        let ticks = self.platform.ticks();
+        match &self.phase {
+            Phase::Playing(playing) => Some(with_playing!(playing, data => data.snapshot(ticks))),
+            _ => None,
+        }
+    }
+
+    /// Continues the game saved in `data`, replacing whatever was going on.
+    pub fn restore_snapshot(&mut self, data: &[u8]) -> Result<(), RestoreError> {
+        let playing_state = Playing::from_snapshot(data, self.platform.ticks())?;
+        self.phase = Phase::Playing(playing_state);
         Ok(())
     }
 
+    /// Saves the game in progress to the platform's storage.
+    pub fn save_game(&mut self) -> Result<(), SaveError> {
+        let snapshot = self.snapshot().ok_or(SaveError::NotPlaying)?;
+        let storage = self.platform.storage().ok_or(SaveError::NoStorage)?;
+        if storage.write(StorageKey::SavedGame, &snapshot) {
+            Ok(())
+        } else {
+            Err(SaveError::WriteFailed)
+        }
+    }
+
+    /// Continues the game last saved to the platform's storage.
+    pub fn restore_game(&mut self) -> Result<(), RestoreError> {
+        let mut buffer = [0; SNAPSHOT_MAX_LEN];
+        let storage = self.platform.storage().ok_or(RestoreError::NoStorage)?;
+        let len = storage
+            .read(StorageKey::SavedGame, &mut buffer)
+            .ok_or(RestoreError::NotFound)?;
+        self.restore_snapshot(&buffer[..len])
+    }
+
+    /// Ends the game in progress if its time limit has run out.
+    fn check_time_limit(&mut self) {
+        let ticks = self.platform.ticks();
+        if let Phase::Playing(playing) = &self.phase {
+            if with_playing!(playing, data => data.is_out_of_time(ticks)) {
+                self.phase = Phase::OutOfTime(false);
+                if let Some(storage) = self.platform.storage() {
+                    storage.remove(StorageKey::SavedGame);
+                }
+            }
+        }
+    }
+
+    /// Draws whatever has changed since the last call, inside the viewport
+    /// and nowhere else on the display.
     pub fn draw<D>(&mut self, display: &mut D) -> Result<(), D::Error>
     where
-        D: DrawTarget<Color = Rgb565>,
+        D: DrawTarget,
+        D::Color: Palette,
     {
+        self.check_time_limit();
+        let ticks = self.platform.ticks();
+        let display = &mut display.clipped(&self.viewport.bounding_box());
         match &mut self.phase {
-            Phase::Playing(playing_state) => {
-                playing_state.draw_playing(self.platform.ticks(), display)?;
+            Phase::Playing(playing) => {
+                let viewport = &self.viewport;
+                with_playing!(playing, data => data.draw_playing(ticks, viewport, display))?;
             }
-            Phase::Done(drawn) => {
+            Phase::OutOfTime(drawn) => {
                 if !*drawn {
                     *drawn = true;
+                    self.viewport.clear(display, D::Color::BACKGROUND)?;
+                    draw_out_of_time(display, &self.viewport)?;
+                }
+            }
+            Phase::Done(finished) => {
+                if !finished.drawn {
+                    finished.drawn = true;
                     self.draw_win(display)?;
-                    self.platform.play_victory_notes();
                 }
             }
-            Phase::Start(drawn) => {
-                if !*drawn {
-                    *drawn = true;
-                    self.draw_start(display)?;
+            Phase::EnterInitials(entry) => {
+                if !entry.drawn {
+                    entry.drawn = true;
+                    self.viewport.clear(display, D::Color::BACKGROUND)?;
+                    draw_initials(
+                        display,
+                        &self.viewport,
+                        entry.difficulty.into(),
+                        entry.place,
+                        &entry.initials,
+                        entry.cursor,
+                    )?;
+                }
+            }
+            Phase::Menu(menu) => {
+                if !menu.drawn {
+                    menu.drawn = true;
+                    menu.draw(&self.config, &self.high_scores, &self.viewport, display)?;
                 }
             }
         }
@@ -318,34 +1498,835 @@ impl<T: PlatformSpecific> Game<T> {
         Ok(())
     }
 
+    /// Draws the results of a won game, or the whole maze after them.
     pub fn draw_win<D>(&self, display: &mut D) -> Result<(), D::Error>
     where
-        D: DrawTarget<Color = Rgb565>,
+        D: DrawTarget,
+        D::Color: Palette,
     {
-        display.clear(Rgb565::BLACK)?;
-        draw_win(display)?;
+        self.viewport.clear(display, D::Color::BACKGROUND)?;
+        if let Phase::Done(finished) = &self.phase {
+            if finished.revealing {
+                let viewport = &self.viewport;
+                with_playing!(&finished.playing, data => data.draw_reveal(viewport, display))?;
+            } else {
+                draw_results(display, &self.viewport, &finished.result)?;
+            }
+        }
         Ok(())
     }
 
-    pub fn key_hit(&mut self) -> bool {
-        match self.phase {
-            Phase::Playing(_) => true,
-            Phase::Start(_) => {
-                self.phase = Phase::Playing(PlayingPhaseData::new(self.platform.ticks()));
+    /// Takes a key from the frontend. A key going down goes on from the
+    /// screens waiting for any key, and otherwise types its character while
+    /// initials are being entered or does its command.
+    pub fn handle_input(&mut self, input: Input) -> InputOutcome {
+        let was_playing = matches!(self.phase, Phase::Playing(_));
+        let handled = match input {
+            Input::KeyDown(key) => !self.key_hit() || self.use_key(key),
+            Input::Repeat(key) => !self.is_waiting_for_key() && self.use_key(key),
+            Input::LongPress(key) if was_playing && key.command.is_none() => {
+                self.quit_playing();
+                true
+            }
+            Input::LongPress(_) | Input::KeyUp(_) => false,
+        };
+        InputOutcome {
+            handled,
+            redraw: self.needs_redraw(),
+            won: was_playing && matches!(self.phase, Phase::Done(_)),
+        }
+    }
+
+    /// Saves the game if it can and goes back to the menu.
+    fn quit_playing(&mut self) {
+        self.save_game().ok();
+        self.show_menu();
+    }
+
+    fn use_key(&mut self, key: Key) -> bool {
+        if key
+            .character
+            .is_some_and(|character| self.type_character(character))
+        {
+            return true;
+        }
+        match key.command {
+            Some(command) => {
+                self.handle_command(command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the screen showing goes on with any key.
+    fn is_waiting_for_key(&self) -> bool {
+        matches!(self.phase, Phase::Done(_) | Phase::OutOfTime(_))
+    }
+
+    /// Whether the next `draw` has more to draw than the clock.
+    fn needs_redraw(&self) -> bool {
+        match &self.phase {
+            Phase::Playing(playing) => {
+                with_playing!(playing, data => data.next_redraw != RedrawMode::Time)
+            }
+            Phase::OutOfTime(drawn) => !drawn,
+            Phase::Done(finished) => !finished.drawn,
+            Phase::EnterInitials(entry) => !entry.drawn,
+            Phase::Menu(menu) => !menu.drawn,
+        }
+    }
+
+    /// Returns true if the key should go on to `handle_command`.
+    fn key_hit(&mut self) -> bool {
+        match &mut self.phase {
+            Phase::Playing(_) | Phase::Menu(_) | Phase::EnterInitials(_) => true,
+            Phase::Done(finished) if !finished.revealing => {
+                finished.revealing = true;
+                finished.drawn = false;
+                false
+            }
+            Phase::Done(finished) => {
+                let result = finished.result;
+                let points = result.score.points();
+                match self.high_scores.place(result.difficulty, points) {
+                    Some(place) => {
+                        self.phase = Phase::EnterInitials(InitialsEntry {
+                            difficulty: result.difficulty,
+                            score: result.score,
+                            place,
+                            initials: self.initials,
+                            cursor: 0,
+                            drawn: false,
+                        })
+                    }
+                    None => self.show_menu(),
+                }
                 false
             }
-            Phase::Done(_) => {
-                self.phase = Phase::Start(false);
+            Phase::OutOfTime(_) => {
+                self.show_menu();
                 false
             }
         }
     }
 
+    /// Takes a character typed on a keyboard, returning true if the game
+    /// used it. While entering initials a letter replaces the one at the
+    /// cursor, a backspace moves back and a newline finishes.
+    fn type_character(&mut self, character: char) -> bool {
+        let entry = match &mut self.phase {
+            Phase::EnterInitials(entry) => entry,
+            _ => return false,
+        };
+        match character {
+            'a'..='z' | 'A'..='Z' => {
+                entry.initials[entry.cursor] = character.to_ascii_uppercase() as u8;
+                entry.move_cursor(true);
+            }
+            '\u{8}' => entry.move_cursor(false),
+            '\n' | '\r' => self.finish_initials(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Goes back to the start menu.
+    pub fn show_menu(&mut self) {
+        let can_continue = self
+            .platform
+            .storage()
+            .is_some_and(|storage| storage.exists(StorageKey::SavedGame));
+        self.phase = Phase::Menu(Menu::new(can_continue));
+    }
+
+    /// Puts the score on the high score table and shows it there.
+    fn finish_initials(&mut self) {
+        let entry = match &self.phase {
+            Phase::EnterInitials(entry) => entry,
+            _ => return,
+        };
+        let difficulty = entry.difficulty;
+        let high_score = HighScore::new(entry.initials, &entry.score);
+        self.initials = entry.initials;
+        let place = self.high_scores.insert(difficulty, high_score);
+        if let Some(storage) = self.platform.storage() {
+            self.high_scores.save(storage);
+        }
+
+        self.show_menu();
+        if let Phase::Menu(menu) = &mut self.phase {
+            menu.show(MenuScreen::HighScores, difficulty as usize);
+            menu.highlight = place;
+        }
+    }
+
+    /// Picks letters with the same commands used to play: turning or moving
+    /// up and down changes the letter, moving forward or right goes on to
+    /// the next and moving left goes back.
+    fn handle_initials_command(&mut self, command: Command) {
+        let entry = match &mut self.phase {
+            Phase::EnterInitials(entry) => entry,
+            _ => return,
+        };
+        match command.relative_to(Direction::North) {
+            Command::TurnRight | Command::MoveUp => entry.change_letter(true),
+            Command::TurnLeft | Command::MoveDown => entry.change_letter(false),
+            Command::MoveLeft => entry.move_cursor(false),
+            Command::MoveForward | Command::MoveRight if entry.is_on_last() => {
+                self.finish_initials()
+            }
+            Command::MoveForward | Command::MoveRight => entry.move_cursor(true),
+            _ => (),
+        }
+    }
+
+    fn start_playing(&mut self) {
+        self.phase = Phase::Playing(Playing::new(self.platform.ticks(), &self.config));
+        self.play_sound(SoundEvent::Start);
+    }
+
+    fn play_sound(&mut self, event: SoundEvent) {
+        if !self.config.muted {
+            self.platform.play_sound(event);
+        }
+    }
+
+    /// Picks the next difficulty in the settings.
+    fn change_difficulty(&mut self) {
+        let count = Difficulty::ALL.len() as u8;
+        let index = (self.config.difficulty as u8 + 1) % count;
+        if let Some(difficulty) = Difficulty::from_index(index) {
+            self.set_config(GameConfig {
+                movement: self.config.movement,
+                muted: self.config.muted,
+                ..difficulty.into()
+            });
+        }
+    }
+
+    /// Picks the next movement scheme in the settings.
+    fn change_movement(&mut self) {
+        let count = MovementScheme::ALL.len() as u8;
+        let index = (self.config.movement as u8 + 1) % count;
+        if let Some(movement) = MovementScheme::from_index(index) {
+            self.set_config(GameConfig {
+                movement,
+                ..self.config
+            });
+        }
+    }
+
+    /// Moves through the menu with the same commands used to play: turning
+    /// or moving up and down changes the selection, moving forward or right
+    /// chooses it and moving left goes back. Any key leaves a page of text,
+    /// except on the high scores where changing the selection shows another
+    /// difficulty.
+    fn handle_menu_command(&mut self, command: Command) {
+        let menu = match &mut self.phase {
+            Phase::Menu(menu) => menu,
+            _ => return,
+        };
+        let command = command.relative_to(Direction::North);
+        let select = matches!(command, Command::MoveForward | Command::MoveRight);
+        let back = matches!(
+            command,
+            Command::MoveLeft | Command::MoveBack | Command::Quit
+        );
+        let changes_selection = matches!(
+            command,
+            Command::TurnLeft | Command::TurnRight | Command::MoveUp | Command::MoveDown
+        );
+
+        let page_item = match menu.screen {
+            MenuScreen::Main | MenuScreen::Settings => None,
+            MenuScreen::HighScores => (!changes_selection).then_some(MenuItem::HighScores),
+            MenuScreen::Instructions => Some(MenuItem::Instructions),
+            MenuScreen::About => Some(MenuItem::About),
+        };
+        if let Some(item) = page_item {
+            menu.show(MenuScreen::Main, item as usize);
+            return;
+        }
+
+        match command {
+            Command::TurnLeft | Command::MoveUp => menu.move_selection(false),
+            Command::TurnRight | Command::MoveDown => menu.move_selection(true),
+            _ => (),
+        }
+
+        match (menu.screen, menu.selected) {
+            (MenuScreen::Main, selected) if select => match MenuItem::ALL[selected] {
+                MenuItem::NewGame => self.start_playing(),
+                MenuItem::Continue => {
+                    self.restore_game().ok();
+                }
+                MenuItem::Settings => menu.show(MenuScreen::Settings, SETTINGS_DIFFICULTY),
+                MenuItem::HighScores => {
+                    menu.show(MenuScreen::HighScores, self.config.difficulty as usize)
+                }
+                MenuItem::Instructions => menu.show(MenuScreen::Instructions, 0),
+                MenuItem::About => menu.show(MenuScreen::About, 0),
+            },
+            (MenuScreen::Settings, SETTINGS_DIFFICULTY) if select => self.change_difficulty(),
+            (MenuScreen::Settings, SETTINGS_MOVEMENT) if select => self.change_movement(),
+            (MenuScreen::Settings, SETTINGS_SOUND) if select => self.set_config(GameConfig {
+                muted: !self.config.muted,
+                ..self.config
+            }),
+            (MenuScreen::Settings, SETTINGS_BACK) if select => {
+                menu.show(MenuScreen::Main, MenuItem::Settings as usize)
+            }
+            (MenuScreen::Settings, _) if back => {
+                menu.show(MenuScreen::Main, MenuItem::Settings as usize)
+            }
+            _ => (),
+        }
+    }
+
     pub fn handle_command(&mut self, command: Command) {
-        if let Phase::Playing(playing_state) = &mut self.phase {
-            if playing_state.handle_command(command) {
-                self.phase = Phase::Done(false);
+        match self.phase {
+            Phase::Menu(_) => return self.handle_menu_command(command),
+            Phase::EnterInitials(_) => return self.handle_initials_command(command),
+            Phase::Playing(_) if command == Command::Quit => return self.quit_playing(),
+            _ => (),
+        }
+
+        let ticks = self.platform.ticks();
+        let (is_win, sound) = match &mut self.phase {
+            Phase::Playing(playing) => with_playing!(playing, data => {
+                let is_win = data.handle_command(command);
+                (is_win, data.sound.take())
+            }),
+            _ => return,
+        };
+        // Winning plays its own tune instead.
+        if let Some(sound) = sound.filter(|_| !is_win) {
+            self.play_sound(sound);
+        }
+        if !is_win {
+            return;
+        }
+
+        if let Phase::Playing(playing) = mem::replace(&mut self.phase, Phase::OutOfTime(true)) {
+            self.phase = Phase::Done(Finished {
+                result: with_playing!(&playing, data => data.result(ticks)),
+                playing,
+                revealing: false,
+                drawn: false,
+            });
+            self.play_sound(SoundEvent::Win);
+        }
+        if let Some(storage) = self.platform.storage() {
+            storage.remove(StorageKey::SavedGame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{maze::direction_to_exit, metrics::MazeMetrics, storage::MemoryStorage};
+    use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor};
+
+    #[derive(Debug, Default)]
+    struct TestPlatform {
+        ticks: u64,
+        sounds: Vec<SoundEvent, 64>,
+        storage: MemoryStorage<SNAPSHOT_MAX_LEN>,
+    }
+
+    impl PlatformSpecific for TestPlatform {
+        fn play_sound(&mut self, event: SoundEvent) {
+            self.sounds.push(event).ok();
+        }
+
+        fn ticks(&mut self) -> u64 {
+            self.ticks
+        }
+
+        fn storage(&mut self) -> Option<&mut dyn Storage> {
+            Some(&mut self.storage)
+        }
+    }
+
+    #[test]
+    fn test_save_and_restore() {
+        let mut game = Game::<TestPlatform>::new();
+        assert_eq!(game.save_game(), Err(SaveError::NotPlaying));
+
+        game.platform.ticks = 1000;
+        game.set_config(Difficulty::Hard.into());
+        game.start_playing();
+        for command in [
+            Command::TurnRight,
+            Command::MoveForward,
+            Command::ShowHints,
+            Command::ToggleShowPosition,
+        ] {
+            game.handle_command(command);
+        }
+        game.platform.ticks = 31000;
+        game.save_game().expect("save");
+        let snapshot = game.snapshot().expect("snapshot");
+
+        // Resume on a platform whose clock has started over.
+        let storage = core::mem::take(&mut game.platform.storage);
+        let mut restored = Game::with_platform(TestPlatform {
+            ticks: 5,
+            storage,
+            ..Default::default()
+        });
+        restored.restore_game().expect("restore");
+        assert_eq!(restored.snapshot(), Some(snapshot));
+        match &restored.phase {
+            Phase::Playing(Playing::Large(playing_state)) => {
+                assert!(playing_state.direction_hint.is_some());
+                assert_eq!(playing_state.hints_left, Some(2));
+                assert_eq!(playing_state.elapsed(5), 30000);
+                assert_eq!(playing_state.shown_time(5), 10 * 60 * 1000 - 30000);
+                let tally = playing_state.tally;
+                assert_eq!(
+                    (tally.moves + tally.bumps, tally.turns, tally.hints),
+                    (1, 1, 1)
+                );
+            }
+            _ => panic!("not playing"),
+        }
+    }
+
+    /// Follows the shortest way out of a small maze.
+    fn escape(game: &mut Game<TestPlatform>) {
+        while let Phase::Playing(Playing::Small(playing_state)) = &mut game.phase {
+            let distances = try_distances_to_exit(&playing_state.maze).expect("distances");
+            let direction =
+                direction_to_exit(&playing_state.maze, &distances, playing_state.position)
+                    .expect("way out");
+            let command = match direction {
+                Direction::Up => Command::MoveUp,
+                Direction::Down => Command::MoveDown,
+                direction => {
+                    playing_state.facing = direction;
+                    Command::MoveForward
+                }
+            };
+            game.handle_command(command);
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let mut game = Game::<TestPlatform>::new();
+        game.set_config(Difficulty::Easy.into());
+        game.start_playing();
+        game.handle_command(Command::TurnLeft);
+        escape(&mut game);
+
+        match game.phase {
+            Phase::Done(Finished { result, .. }) => {
+                let score = result.score;
+                assert_eq!(score.tally.moves, score.par);
+                assert_eq!(score.tally.turns, 1);
+                assert_eq!(score.points(), Score::PERFECT);
+                assert_eq!(result.room_count(), 27);
+                assert_eq!(result.visited, result.shortest);
+                assert_eq!(result.visited.len(), score.par as usize);
+            }
+            _ => panic!("didn't win"),
+        }
+    }
+
+    fn press(game: &mut Game<TestPlatform>, key: impl Into<Key>) -> InputOutcome {
+        game.handle_input(Input::KeyDown(key.into()))
+    }
+
+    fn typed(character: char) -> Key {
+        Key {
+            character: Some(character),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sounds() {
+        let mut game = Game::<TestPlatform>::new();
+        game.set_config(Difficulty::Easy.into());
+        game.start_playing();
+        game.handle_command(Command::TurnLeft);
+        game.handle_command(Command::ShowHints);
+        assert_eq!(
+            game.platform.sounds,
+            [SoundEvent::Start, SoundEvent::Turn, SoundEvent::Hint]
+        );
+
+        // The last move plays the win instead of a step, and drawing the
+        // results again, or the maze after them, doesn't replay it.
+        escape(&mut game);
+        assert_eq!(game.platform.sounds.last(), Some(&SoundEvent::Win));
+        let sounds = game.platform.sounds.len();
+        let mut display = MockDisplay::<BinaryColor>::new();
+        display.set_allow_overdraw(true);
+        game.draw(&mut display).expect("draw");
+        game.set_viewport(game.viewport);
+        game.draw(&mut display).expect("draw");
+        game.key_hit();
+        game.draw(&mut display).expect("draw");
+        assert_eq!(game.platform.sounds.len(), sounds);
+
+        game.platform.sounds.clear();
+        game.set_config(GameConfig {
+            muted: true,
+            ..Difficulty::Easy.into()
+        });
+        game.start_playing();
+        game.handle_command(Command::TurnLeft);
+        assert!(game.platform.sounds.is_empty());
+    }
+
+    #[test]
+    fn test_input() {
+        let mut game = Game::<TestPlatform>::new();
+        game.set_config(Difficulty::Easy.into());
+        game.start_playing();
+        let mut display = MockDisplay::<BinaryColor>::new();
+        display.set_allow_overdraw(true);
+        game.draw(&mut display).expect("draw");
+        assert!(
+            !game
+                .handle_input(Input::KeyUp(Command::TurnLeft.into()))
+                .handled
+        );
+        let outcome = press(&mut game, Command::TurnLeft);
+        assert!(outcome.handled && outcome.redraw && !outcome.won);
+        assert!(!press(&mut game, Key::default()).handled);
+
+        // Repeats don't go on from the results, so holding the last move
+        // doesn't skip them.
+        escape(&mut game);
+        assert!(
+            !game
+                .handle_input(Input::Repeat(Command::MoveForward.into()))
+                .handled
+        );
+        assert!(matches!(
+            game.phase,
+            Phase::Done(Finished {
+                revealing: false,
+                ..
+            })
+        ));
+
+        game.start_playing();
+        let turn = Input::LongPress(Command::TurnLeft.into());
+        assert!(!game.handle_input(turn).handled);
+        let outcome = game.handle_input(Input::LongPress(Key::default()));
+        assert!(outcome.handled && outcome.redraw);
+        assert!(matches!(
+            game.phase,
+            Phase::Menu(Menu {
+                can_continue: true,
+                ..
+            })
+        ));
+
+        game.restore_game().expect("restore");
+        press(&mut game, Command::Quit);
+        assert!(matches!(
+            game.phase,
+            Phase::Menu(Menu {
+                can_continue: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_high_scores() {
+        let mut game = Game::<TestPlatform>::new();
+        game.set_config(Difficulty::Easy.into());
+        game.start_playing();
+        escape(&mut game);
+
+        // The results, then the whole maze, then the initials.
+        assert!(press(&mut game, Key::default()).handled);
+        assert!(matches!(
+            game.phase,
+            Phase::Done(Finished {
+                revealing: true,
+                ..
+            })
+        ));
+        press(&mut game, Key::default());
+        assert!(matches!(game.phase, Phase::EnterInitials(_)));
+        game.handle_command(Command::TurnRight);
+        game.handle_command(Command::MoveForward);
+        assert!(press(&mut game, typed('x')).handled);
+        assert!(!press(&mut game, typed('1')).handled);
+        assert!(press(&mut game, typed('\n')).handled);
+
+        let table = game.high_scores().table(Difficulty::Easy);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].initials(), "BXA");
+        assert_eq!(table[0].points, Score::PERFECT);
+        match &game.phase {
+            Phase::Menu(menu) => {
+                assert_eq!(menu.screen, MenuScreen::HighScores);
+                assert_eq!(menu.highlight, Some(0));
+            }
+            _ => panic!("not showing the high scores"),
+        }
+        assert!(!press(&mut game, typed('x')).handled);
+
+        // The table is kept for next time.
+        let storage = core::mem::take(&mut game.platform.storage);
+        let mut game = Game::with_platform(TestPlatform {
+            ticks: 0,
+            storage,
+            ..Default::default()
+        });
+        assert_eq!(game.high_scores().table(Difficulty::Easy).len(), 1);
+        game.set_config(Difficulty::Easy.into());
+        game.start_playing();
+        escape(&mut game);
+        press(&mut game, Key::default());
+        press(&mut game, Key::default());
+        game.handle_command(Command::MoveForward);
+        game.handle_command(Command::MoveForward);
+        game.handle_command(Command::MoveForward);
+        let table = game.high_scores().table(Difficulty::Easy);
+        assert_eq!(table[1].initials(), "AAA");
+    }
+
+    #[test]
+    fn test_map() {
+        let mut game = Game::<TestPlatform>::new();
+        game.start_playing();
+        let playing_state = match &mut game.phase {
+            Phase::Playing(Playing::Standard(playing_state)) => playing_state,
+            _ => panic!("not playing"),
+        };
+        let start = playing_state.position;
+        let behind = 1 << playing_state.facing.opposite() as u8;
+        assert_eq!(playing_state.map_room(&start).seen, ALL_SIDES & !behind);
+        playing_state.handle_command(Command::TurnLeft);
+        playing_state.handle_command(Command::TurnLeft);
+        let room = playing_state.map_room(&start);
+        assert!(room.visited);
+        assert_eq!(room.seen, ALL_SIDES);
+        for direction in Direction::ALL {
+            let has_door = playing_state.maze.get_cell(&start).has_door(direction);
+            assert_eq!(room.door(direction), Some(has_door));
+        }
+
+        // Up and down change floor, staying inside the maze, and moving
+        // just closes the map.
+        playing_state.handle_command(Command::ShowMap);
+        assert_eq!(playing_state.map_floor, Some(start.z));
+        for _ in 0..6 {
+            playing_state.handle_command(Command::MoveDown);
+        }
+        assert_eq!(playing_state.map_floor, Some(0));
+        playing_state.handle_command(Command::MoveUp);
+        assert_eq!(playing_state.map_floor, Some(1));
+        playing_state.handle_command(Command::MoveForward);
+        assert_eq!(playing_state.map_floor, None);
+        assert_eq!(playing_state.position, start);
+    }
+
+    #[test]
+    fn test_corridor() {
+        // Three rooms in a row with the way out at the east end.
+        let mut maze = Maze::<3, 1, 1>::default();
+        maze.carve_passage(Coord::default(), Direction::East);
+        maze.carve_passage(Coord { x: 1, y: 0, z: 0 }, Direction::East);
+        maze.set_door(&Coord { x: 2, y: 0, z: 0 }, Direction::East, true);
+        let mut playing_state = PlayingPhaseData {
+            maze,
+            facing: Direction::East,
+            ..Default::default()
+        };
+
+        let corridor = playing_state.corridor();
+        assert_eq!(corridor.len(), 2);
+        assert!(corridor[0].front && !corridor[0].left);
+        assert!(corridor[1].front);
+        playing_state.facing = Direction::West;
+        assert!(playing_state.corridor().is_empty());
+    }
+
+    #[test]
+    fn test_rated_maze() {
+        for difficulty in Difficulty::ALL {
+            let config = GameConfig::from(difficulty);
+            for ticks in [0, 1000, 123456] {
+                let playing = Playing::new(ticks, &config);
+                let metrics = with_playing!(&playing, playing_state => {
+                    MazeMetrics::measure(&playing_state.maze, playing_state.position)
+                })
+                .expect("metrics");
+                assert_eq!(metrics.difficulty(config.algorithm), difficulty);
             }
         }
     }
+
+    #[test]
+    fn test_movement() {
+        // Three rooms in a row, west to east, with the player in the middle.
+        let mut maze = Maze::<3, 1, 1>::default();
+        maze.carve_passage(Coord::default(), Direction::East);
+        maze.carve_passage(Coord { x: 1, y: 0, z: 0 }, Direction::East);
+        let middle = Coord { x: 1, y: 0, z: 0 };
+        let mut playing_state = PlayingPhaseData {
+            maze,
+            position: middle,
+            facing: Direction::North,
+            ..Default::default()
+        };
+
+        // Relative moves ignore the compass.
+        playing_state.handle_command(Command::Move(Direction::East));
+        assert_eq!(playing_state.position, middle);
+        playing_state.handle_command(Command::AboutFace);
+        assert_eq!(playing_state.facing, Direction::South);
+        playing_state.handle_command(Command::MoveLeft);
+        assert_eq!(playing_state.position.x, 2);
+        playing_state.direction_hint = Some(Direction::West);
+        assert_eq!(playing_state.hint_label(), Some("Right"));
+
+        // The compass ignores relative moves.
+        playing_state.movement = MovementScheme::Absolute;
+        assert_eq!(playing_state.hint_label(), Some("West"));
+        playing_state.handle_command(Command::MoveRight);
+        assert_eq!(playing_state.position.x, 2);
+        playing_state.handle_command(Command::Face(Direction::East));
+        playing_state.handle_command(Command::Move(Direction::West));
+        assert_eq!(playing_state.position, middle);
+        assert_eq!(playing_state.facing, Direction::East);
+
+        // Either works in the hybrid, including stepping back.
+        playing_state.movement = MovementScheme::Hybrid;
+        playing_state.handle_command(Command::MoveBack);
+        assert_eq!(playing_state.position.x, 0);
+        playing_state.handle_command(Command::Move(Direction::East));
+        assert_eq!(playing_state.position, middle);
+        assert_eq!(playing_state.tally.moves, 4);
+        assert_eq!(playing_state.tally.turns, 2);
+        assert_eq!(playing_state.tally.bumps, 0);
+    }
+
+    #[test]
+    fn test_restore_errors() {
+        let mut game = Game::<TestPlatform>::new();
+        assert_eq!(game.restore_game(), Err(RestoreError::NotFound));
+        assert_eq!(game.restore_snapshot(b"QMZ"), Err(RestoreError::Corrupt));
+
+        game.start_playing();
+        let mut snapshot = game.snapshot().expect("snapshot");
+        for version in [0, SNAPSHOT_VERSION + 1] {
+            snapshot[3] = version;
+            assert_eq!(
+                game.restore_snapshot(&snapshot),
+                Err(RestoreError::UnsupportedVersion(version))
+            );
+        }
+        snapshot.pop();
+        snapshot[3] = SNAPSHOT_VERSION;
+        assert_eq!(game.restore_snapshot(&snapshot), Err(RestoreError::Corrupt));
+    }
+
+    #[test]
+    fn test_viewport() {
+        let mut game = Game::<TestPlatform>::new();
+        game.start_playing();
+        let viewport = Viewport::new(Point::new(8, 4), Size::new(48, 40));
+        game.set_viewport(viewport);
+
+        // The status bar's text is wider than the viewport, but is cut off.
+        let mut display = MockDisplay::<BinaryColor>::new();
+        display.set_allow_overdraw(true);
+        game.draw(&mut display).expect("draw");
+        assert_eq!(display.affected_area(), viewport.bounding_box());
+    }
+
+    #[test]
+    fn test_menu() {
+        let mut game = Game::<TestPlatform>::new();
+
+        // Settings, then pick Hard and go back.
+        game.handle_command(Command::MoveDown);
+        game.handle_command(Command::TurnRight);
+        game.handle_command(Command::MoveForward);
+        game.handle_command(Command::MoveForward);
+        assert_eq!(game.config().difficulty, Difficulty::Hard);
+        game.handle_command(Command::TurnRight);
+        game.handle_command(Command::MoveForward);
+        assert_eq!(game.config().movement, MovementScheme::Absolute);
+        game.handle_command(Command::MoveLeft);
+
+        // Wrap around to About and back out of it with any key.
+        game.handle_command(Command::TurnLeft);
+        game.handle_command(Command::TurnLeft);
+        game.handle_command(Command::TurnLeft);
+        game.handle_command(Command::MoveRight);
+        match &game.phase {
+            Phase::Menu(menu) => assert_eq!(menu.screen, MenuScreen::About),
+            _ => panic!("not in the menu"),
+        }
+        game.handle_command(Command::ToggleShowPosition);
+
+        // Nothing to continue yet.
+        game.handle_command(Command::MoveUp);
+        game.handle_command(Command::MoveUp);
+        game.handle_command(Command::MoveUp);
+        game.handle_command(Command::MoveUp);
+        game.handle_command(Command::MoveForward);
+        match &game.phase {
+            Phase::Menu(menu) => {
+                assert_eq!(MenuItem::ALL[menu.selected], MenuItem::Continue);
+                assert!(!menu.can_continue);
+            }
+            _ => panic!("not in the menu"),
+        }
+
+        game.handle_command(Command::MoveUp);
+        game.handle_command(Command::MoveForward);
+        game.save_game().expect("save");
+        match &game.phase {
+            Phase::Playing(Playing::Large(playing_state)) => {
+                assert_eq!(playing_state.hints_left, Some(3));
+            }
+            _ => panic!("not playing a large maze"),
+        }
+
+        game.show_menu();
+        game.handle_command(Command::MoveDown);
+        game.handle_command(Command::MoveForward);
+        assert!(game.snapshot().is_some());
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut game = Game::<TestPlatform>::new();
+        game.set_config(GameConfig {
+            hints: Some(1),
+            time_limit: Some(1000),
+            ..Default::default()
+        });
+        game.start_playing();
+        game.handle_command(Command::ShowHints);
+        game.handle_command(Command::ShowHints);
+        let Phase::Playing(Playing::Standard(playing_state)) = &mut game.phase else {
+            panic!("not playing");
+        };
+        assert_eq!(playing_state.hints_left, Some(0));
+        playing_state.direction_hint = None;
+        playing_state.show_direction_hint();
+        assert_eq!(playing_state.direction_hint, None);
+
+        game.platform.ticks = 1000;
+        game.check_time_limit();
+        assert!(matches!(game.phase, Phase::OutOfTime(_)));
+        press(&mut game, Key::default());
+        assert!(matches!(game.phase, Phase::Menu(_)));
+    }
 }
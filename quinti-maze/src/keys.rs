@@ -0,0 +1,342 @@
+use crate::{game::Command, maze::Direction};
+use heapless::LinearMap;
+
+/// A key on whatever the platform has for input, independent of how the
+/// platform reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyId {
+    /// A key with a character on it, with letters in upper case.
+    Character(char),
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl KeyId {
+    /// The key with `character` on it.
+    pub fn character(character: char) -> Self {
+        Self::Character(character.to_ascii_uppercase())
+    }
+
+    /// The key with a name like `W`, `#` or `Left`, as written in a key
+    /// bindings file.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut characters = name.chars();
+        if let (Some(character), None) = (characters.next(), characters.next()) {
+            return Some(Self::character(character));
+        }
+        [
+            ("left", Self::Left),
+            ("right", Self::Right),
+            ("up", Self::Up),
+            ("down", Self::Down),
+        ]
+        .into_iter()
+        .find(|(key_name, _)| name.eq_ignore_ascii_case(key_name))
+        .map(|(_, key)| key)
+    }
+}
+
+/// The sets of key bindings that come with the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Letters and arrows on a computer keyboard.
+    Keyboard,
+    /// The device's twelve key keypad.
+    Keypad,
+    /// The letters used by the 1982 BASIC program, which moves by the
+    /// compass.
+    Basic,
+}
+
+impl Layout {
+    pub const ALL: [Self; 3] = [Self::Keyboard, Self::Keypad, Self::Basic];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Keyboard => "keyboard",
+            Self::Keypad => "keypad",
+            Self::Basic => "basic",
+        }
+    }
+
+    fn bindings(&self) -> &'static [(KeyId, Command)] {
+        match self {
+            Self::Keyboard => &[
+                (KeyId::Character('W'), Command::MoveForward),
+                (KeyId::Character('D'), Command::MoveRight),
+                (KeyId::Character('A'), Command::MoveLeft),
+                (KeyId::Character('S'), Command::MoveBack),
+                (KeyId::Character('E'), Command::MoveUp),
+                (KeyId::Character('Q'), Command::MoveDown),
+                (KeyId::Left, Command::TurnLeft),
+                (KeyId::Right, Command::TurnRight),
+                (KeyId::Down, Command::AboutFace),
+                (KeyId::Character('/'), Command::ToggleShowPosition),
+                (KeyId::Character('='), Command::ShowHints),
+                (KeyId::Character('M'), Command::ShowMap),
+            ],
+            Self::Keypad => &[
+                (KeyId::Character('1'), Command::MoveDown),
+                (KeyId::Character('2'), Command::MoveForward),
+                (KeyId::Character('3'), Command::MoveUp),
+                (KeyId::Character('4'), Command::MoveLeft),
+                (KeyId::Character('5'), Command::ShowMap),
+                (KeyId::Character('6'), Command::MoveRight),
+                (KeyId::Character('7'), Command::TurnLeft),
+                (KeyId::Character('8'), Command::MoveBack),
+                (KeyId::Character('9'), Command::TurnRight),
+                (KeyId::Character('0'), Command::AboutFace),
+                (KeyId::Character('*'), Command::ToggleShowPosition),
+                (KeyId::Character('#'), Command::ShowHints),
+            ],
+            // One key can't say which way to face, so F turns to face the
+            // next way round.
+            Self::Basic => &[
+                (KeyId::Character('U'), Command::MoveUp),
+                (KeyId::Character('D'), Command::MoveDown),
+                (KeyId::Character('N'), Command::Move(Direction::North)),
+                (KeyId::Character('S'), Command::Move(Direction::South)),
+                (KeyId::Character('E'), Command::Move(Direction::East)),
+                (KeyId::Character('W'), Command::Move(Direction::West)),
+                (KeyId::Character('F'), Command::TurnRight),
+                (KeyId::Character('Q'), Command::Quit),
+                (KeyId::Character('*'), Command::ToggleShowPosition),
+                (KeyId::Character('?'), Command::ShowHints),
+            ],
+        }
+    }
+}
+
+/// The names commands have in a key bindings file.
+const COMMAND_NAMES: &[(&str, Command)] = &[
+    ("forward", Command::MoveForward),
+    ("left", Command::MoveLeft),
+    ("right", Command::MoveRight),
+    ("up", Command::MoveUp),
+    ("down", Command::MoveDown),
+    ("back", Command::MoveBack),
+    ("north", Command::Move(Direction::North)),
+    ("south", Command::Move(Direction::South)),
+    ("east", Command::Move(Direction::East)),
+    ("west", Command::Move(Direction::West)),
+    ("face-north", Command::Face(Direction::North)),
+    ("face-south", Command::Face(Direction::South)),
+    ("face-east", Command::Face(Direction::East)),
+    ("face-west", Command::Face(Direction::West)),
+    ("about-face", Command::AboutFace),
+    ("turn-left", Command::TurnLeft),
+    ("turn-right", Command::TurnRight),
+    ("position", Command::ToggleShowPosition),
+    ("hints", Command::ShowHints),
+    ("map", Command::ShowMap),
+    ("quit", Command::Quit),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingsError {
+    /// A line, counting from 1, that isn't `name = value`.
+    Syntax(usize),
+    UnknownKey(usize),
+    UnknownCommand(usize),
+    UnknownLayout(usize),
+    TooMany,
+}
+
+/// Which command each key gives, which can be changed while running.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    bindings: LinearMap<KeyId, Command, { KeyBindings::MAX }>,
+}
+
+impl KeyBindings {
+    pub const MAX: usize = 32;
+
+    pub fn new(layout: Layout) -> Self {
+        let mut key_bindings = Self {
+            bindings: LinearMap::new(),
+        };
+        key_bindings.use_layout(layout);
+        key_bindings
+    }
+
+    /// Replaces all the bindings with those of `layout`.
+    pub fn use_layout(&mut self, layout: Layout) {
+        self.bindings.clear();
+        for &(key, command) in layout.bindings() {
+            self.bind(key, command).ok();
+        }
+    }
+
+    pub fn command(&self, key: KeyId) -> Option<Command> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// The keys that give `command`.
+    pub fn keys_for(&self, command: Command) -> impl Iterator<Item = KeyId> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, &bound)| bound == command)
+            .map(|(&key, _)| key)
+    }
+
+    /// Makes `key` give `command`, in place of anything it gave before.
+    pub fn bind(&mut self, key: KeyId, command: Command) -> Result<(), BindingsError> {
+        self.bindings
+            .insert(key, command)
+            .map(|_| ())
+            .map_err(|_| BindingsError::TooMany)
+    }
+
+    pub fn unbind(&mut self, key: KeyId) {
+        self.bindings.remove(&key);
+    }
+
+    /// Applies the lines of a key bindings file, which look like `M = map`,
+    /// `X = none` to take a key's command away, or `layout = basic` to start
+    /// again from one of the layouts. Blank lines and comments starting with
+    /// `#` are skipped.
+    pub fn load(&mut self, text: &str) -> Result<(), BindingsError> {
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            // The first character is part of the name, so `=` and `#` can
+            // be bound too.
+            let split = line
+                .char_indices()
+                .skip(1)
+                .find(|&(_, character)| character == '=')
+                .map(|(at, _)| (line[..at].trim(), line[at + 1..].trim()));
+            let is_comment = line.starts_with('#') && split.is_none_or(|(name, _)| name != "#");
+            if line.is_empty() || is_comment {
+                continue;
+            }
+            let (name, value) = split.ok_or(BindingsError::Syntax(number))?;
+
+            if name.eq_ignore_ascii_case("layout") {
+                let layout = Layout::ALL
+                    .into_iter()
+                    .find(|layout| value.eq_ignore_ascii_case(layout.name()))
+                    .ok_or(BindingsError::UnknownLayout(number))?;
+                self.use_layout(layout);
+                continue;
+            }
+
+            let key = KeyId::from_name(name).ok_or(BindingsError::UnknownKey(number))?;
+            if value.eq_ignore_ascii_case("none") {
+                self.unbind(key);
+                continue;
+            }
+            let command = COMMAND_NAMES
+                .iter()
+                .find(|(command_name, _)| value.eq_ignore_ascii_case(command_name))
+                .map(|&(_, command)| command)
+                .ok_or(BindingsError::UnknownCommand(number))?;
+            self.bind(key, command)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        let keyboard = KeyBindings::new(Layout::Keyboard);
+        assert_eq!(
+            keyboard.command(KeyId::character('w')),
+            Some(Command::MoveForward)
+        );
+        assert_eq!(keyboard.command(KeyId::Left), Some(Command::TurnLeft));
+        assert_eq!(keyboard.command(KeyId::character('x')), None);
+
+        let keypad = KeyBindings::new(Layout::Keypad);
+        assert_eq!(
+            keypad.command(KeyId::character('#')),
+            Some(Command::ShowHints)
+        );
+
+        let basic = KeyBindings::new(Layout::Basic);
+        assert_eq!(basic.command(KeyId::character('q')), Some(Command::Quit));
+        assert_eq!(basic.command(KeyId::character('U')), Some(Command::MoveUp));
+    }
+
+    #[test]
+    fn test_basic_layout() {
+        // The keys read from A$ in lines 1035 to 1120 of quinti-maze.bas.
+        let basic = KeyBindings::new(Layout::Basic);
+        for (key, command) in [
+            ('*', Command::ToggleShowPosition),
+            ('Q', Command::Quit),
+            ('U', Command::MoveUp),
+            ('D', Command::MoveDown),
+            ('N', Command::Move(Direction::North)),
+            ('S', Command::Move(Direction::South)),
+            ('E', Command::Move(Direction::East)),
+            ('?', Command::ShowHints),
+            ('W', Command::Move(Direction::West)),
+            ('F', Command::TurnRight),
+        ] {
+            assert_eq!(basic.command(KeyId::character(key)), Some(command));
+        }
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut bindings = KeyBindings::new(Layout::Keyboard);
+        bindings.bind(KeyId::Up, Command::MoveForward).unwrap();
+        bindings.unbind(KeyId::character('W'));
+        assert_eq!(bindings.command(KeyId::Up), Some(Command::MoveForward));
+        assert_eq!(bindings.command(KeyId::character('W')), None);
+        assert!(bindings.keys_for(Command::MoveForward).eq([KeyId::Up]));
+    }
+
+    #[test]
+    fn test_load() {
+        let mut bindings = KeyBindings::new(Layout::Keyboard);
+        let text = "# Play like it's 1982\n\
+                    layout = basic\n\
+                    \n\
+                    S = turn-left\n\
+                    Up = forward\n\
+                    # = map\n\
+                    = = position\n\
+                    ? = none\n";
+        assert_eq!(bindings.load(text), Ok(()));
+        assert_eq!(
+            bindings.command(KeyId::character('W')),
+            Some(Command::Move(Direction::West))
+        );
+        assert_eq!(
+            bindings.command(KeyId::character('S')),
+            Some(Command::TurnLeft)
+        );
+        assert_eq!(bindings.command(KeyId::Up), Some(Command::MoveForward));
+        assert_eq!(
+            bindings.command(KeyId::character('#')),
+            Some(Command::ShowMap)
+        );
+        assert_eq!(
+            bindings.command(KeyId::character('=')),
+            Some(Command::ToggleShowPosition)
+        );
+        assert_eq!(bindings.command(KeyId::character('?')), None);
+
+        assert_eq!(bindings.load("M"), Err(BindingsError::Syntax(1)));
+        assert_eq!(
+            bindings.load("\nTab = map"),
+            Err(BindingsError::UnknownKey(2))
+        );
+        assert_eq!(
+            bindings.load("M = jump"),
+            Err(BindingsError::UnknownCommand(1))
+        );
+        assert_eq!(
+            bindings.load("layout = dvorak"),
+            Err(BindingsError::UnknownLayout(1))
+        );
+    }
+}
@@ -1,8 +1,15 @@
 #![allow(clippy::large_enum_variant)]
 #![no_std]
 
+pub mod algorithms;
 pub mod draw;
 pub mod game;
+pub mod keys;
 pub mod maze;
-#[cfg(any(unix,windows))]
+pub mod metrics;
+pub mod palette;
+pub mod score;
+pub mod sequencer;
+pub mod storage;
+#[cfg(any(unix, windows))]
 pub mod time;
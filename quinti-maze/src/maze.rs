@@ -1,8 +1,22 @@
-use heapless::{Deque, Vec};
+use crate::algorithms::{Algorithm, MazeAlgorithm};
+use core::{
+    fmt,
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
 use rand::{prelude::*, Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeError {
+    /// The coordinate isn't inside the maze.
+    OutOfBounds(Coord),
+    /// The two coordinates aren't neighbouring rooms.
+    NotAdjacent(Coord, Coord),
+    /// A fixed capacity list ran out of room.
+    CapacityExceeded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     North,
     South,
@@ -13,6 +27,21 @@ pub enum Direction {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|direction| *direction as u8 == index)
+    }
+
     pub fn opposite(&self) -> Self {
         match self {
             Self::North => Self::South,
@@ -55,6 +84,14 @@ pub enum VisibleDoors {
 }
 
 impl VisibleDoors {
+    pub const ALL: [VisibleDoors; 5] = [
+        VisibleDoors::Left,
+        VisibleDoors::Forward,
+        VisibleDoors::Right,
+        VisibleDoors::Up,
+        VisibleDoors::Down,
+    ];
+
     pub fn direction(&self, facing: Direction) -> Direction {
         match self {
             Self::Up => Direction::Up,
@@ -105,116 +142,288 @@ impl Coord {
     }
 
     pub fn direction_to(&self, target: Self) -> Direction {
-        let delta_x = self.x - target.x;
-        if delta_x != 0 {
-            return if delta_x < 0 {
-                Direction::East
-            } else {
-                Direction::West
-            };
-        }
-        let delta_y = self.y - target.y;
-        if delta_y != 0 {
-            return if delta_y < 0 {
-                Direction::South
-            } else {
-                Direction::North
-            };
-        }
-        let delta_z = self.z - target.z;
-        if delta_z != 0 {
-            return if delta_z < 0 {
-                Direction::Up
-            } else {
-                Direction::Down
-            };
-        }
-        panic!("impossible direction");
+        self.try_direction_to(target).expect("impossible direction")
     }
-}
 
-type Doors = [bool; 6];
+    /// The direction to `target`, which must be a neighbouring room.
+    pub fn try_direction_to(&self, target: Self) -> Result<Direction, MazeError> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| self.move_in_direction(*direction) == target)
+            .ok_or(MazeError::NotAdjacent(*self, target))
+    }
+}
 
-#[derive(Debug, Default, Clone, Copy)]
+/// The doors of one room, as seen from inside it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Cell {
-    pub doors: Doors,
-    pub visited: bool,
+    doors: u8,
 }
 
 impl Cell {
+    const fn door_bit(direction: Direction) -> u8 {
+        1 << direction as u8
+    }
+
     pub fn has_door(&self, direction: Direction) -> bool {
-        self.doors[direction as usize]
+        self.doors & Self::door_bit(direction) != 0
     }
 
     pub fn left(&self, facing: Direction) -> bool {
-        self.doors[VisibleDoors::Left.direction_as_index(facing)]
+        self.has_door(VisibleDoors::Left.direction(facing))
     }
 
     pub fn front(&self, facing: Direction) -> bool {
-        self.doors[VisibleDoors::Forward.direction_as_index(facing)]
+        self.has_door(VisibleDoors::Forward.direction(facing))
     }
 
     pub fn right(&self, facing: Direction) -> bool {
-        self.doors[VisibleDoors::Right.direction_as_index(facing)]
+        self.has_door(VisibleDoors::Right.direction(facing))
     }
 
     pub fn top(&self) -> bool {
-        self.doors[Direction::Up as usize]
+        self.has_door(Direction::Up)
     }
 
     pub fn bottom(&self) -> bool {
-        self.doors[Direction::Down as usize]
-    }
-
-    pub fn remove_wall(&mut self, direction: &Direction) {
-        self.doors[*direction as usize] = true;
+        self.has_door(Direction::Down)
     }
 }
 
-#[derive(Debug)]
+/// A maze stored as three bits per room, one for each wall it shares with
+/// the rooms east of it, south of it and above it. Each wall between two
+/// rooms is stored once, so neighbouring rooms always agree about their
+/// doors. The one door out of the maze is kept apart.
+///
+/// The bits for each direction are kept a row at a time, so a maze can be
+/// up to 8 rooms wide.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Maze<const X: usize, const Y: usize, const Z: usize> {
-    cells: [[[Cell; X]; Y]; Z],
+    /// A row of bits for each floor and row, for the walls east, south and
+    /// up in the order of `PASSAGES`.
+    walls: [[[u8; Y]; Z]; 3],
+    /// The index of the room with the door out, and the way it leads.
+    exit: Option<(u16, Direction)>,
 }
 
 impl<const X: usize, const Y: usize, const Z: usize> Default for Maze<X, Y, Z> {
     fn default() -> Self {
+        let () = Self::ROW_FITS;
         Self {
-            cells: [[[Cell::default(); X]; Y]; Z],
+            walls: [[[0; Y]; Z]; 3],
+            exit: None,
         }
     }
 }
 
 impl<const X: usize, const Y: usize, const Z: usize> Maze<X, Y, Z> {
+    /// The walls that are stored, each in the room west, north or below it.
+    const PASSAGES: [Direction; 3] = [Direction::East, Direction::South, Direction::Up];
+
+    const ROW_FITS: () = assert!(X <= u8::BITS as usize, "mazes are at most 8 rooms wide");
+
     pub const fn dimensions() -> (usize, usize, usize) {
         (X, Y, Z)
     }
 
-    const fn cell_count() -> usize {
+    pub const fn cell_count() -> usize {
         let dimensions = Self::dimensions();
         dimensions.0 * dimensions.1 * dimensions.2
     }
 
-    fn validate_coord(&self, coord: &Coord) {
-        let dimensions = Self::dimensions();
-        assert!(0 <= coord.x);
-        assert!(0 <= coord.y);
-        assert!(0 <= coord.z);
-        assert!((coord.x as usize) < dimensions.0);
-        assert!((coord.y as usize) < dimensions.1);
-        assert!((coord.z as usize) < dimensions.2);
+    pub fn validate_coord(coord: &Coord) -> Result<(), MazeError> {
+        if Self::is_outside(coord) {
+            Err(MazeError::OutOfBounds(*coord))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The number of bytes `as_bytes` returns.
+    pub const fn byte_len() -> usize {
+        Self::PASSAGES.len() * Y * Z
+    }
+
+    /// The packed walls, for saving the maze along with its `exit`.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.walls.as_flattened().as_flattened()
+    }
+
+    /// The room with the door out of the maze, and the way it leads.
+    pub fn exit(&self) -> Option<(Coord, Direction)> {
+        self.exit
+            .map(|(index, direction)| (Self::coord_at(index as usize), direction))
+    }
+
+    /// Rebuilds a maze from the bytes returned by `as_bytes` and its `exit`,
+    /// or `None` if any bits are set for walls that aren't between rooms, or
+    /// the exit doesn't lead out.
+    pub fn from_bytes(bytes: &[u8], exit: Option<(Coord, Direction)>) -> Option<Self> {
+        if bytes.len() != Self::byte_len() {
+            return None;
+        }
+        let mut maze = Self::default();
+        maze.walls
+            .as_flattened_mut()
+            .as_flattened_mut()
+            .copy_from_slice(bytes);
+        if maze.has_unused_bits() {
+            return None;
+        }
+        if let Some((coord, direction)) = exit {
+            Self::validate_coord(&coord).ok()?;
+            if Self::neighbor(coord, direction).is_some() {
+                return None;
+            }
+            maze.set_door(&coord, direction, true);
+        }
+        Some(maze)
+    }
+
+    /// The bits of the row `y` of floor `z` that can hold the wall in
+    /// `PASSAGES[plane]`, leaving out those that would lead out of the maze.
+    fn usable_bits(plane: usize, y: usize, z: usize) -> u8 {
+        let row = u8::MAX >> (u8::BITS as usize - X);
+        match Self::PASSAGES[plane] {
+            Direction::East => row >> 1,
+            Direction::South if y + 1 < Y => row,
+            Direction::Up if z + 1 < Z => row,
+            _ => 0,
+        }
+    }
+
+    /// The rows, as their west rooms, with bits set that aren't walls
+    /// between rooms.
+    fn rows_with_unused_bits(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..Z).flat_map(move |z| {
+            (0..Y)
+                .filter(move |y| {
+                    (0..Self::PASSAGES.len()).any(|plane| {
+                        self.walls[plane][z][*y] & !Self::usable_bits(plane, *y, z) != 0
+                    })
+                })
+                .map(move |y| Coord {
+                    x: 0,
+                    y: y as isize,
+                    z: z as isize,
+                })
+        })
+    }
+
+    fn has_unused_bits(&self) -> bool {
+        self.rows_with_unused_bits().next().is_some()
+    }
+
+    /// The wall the door leaving `coord` in `direction` goes through, as its
+    /// index in `PASSAGES` and the room west, north or below it, or `None`
+    /// for a door out of the maze.
+    fn wall(coord: &Coord, direction: Direction) -> Option<(usize, Coord)> {
+        let next = Self::neighbor(*coord, direction)?;
+        let (passage, room) = match direction {
+            Direction::West | Direction::North | Direction::Down => (direction.opposite(), next),
+            _ => (direction, *coord),
+        };
+        let plane = Self::PASSAGES
+            .iter()
+            .position(|stored| *stored == passage)?;
+        Some((plane, room))
+    }
+
+    fn wall_bits_mut(&mut self, plane: usize, room: &Coord) -> &mut u8 {
+        &mut self.walls[plane][room.z as usize][room.y as usize]
+    }
+
+    fn has_wall_door(&self, plane: usize, room: &Coord) -> bool {
+        self.walls[plane][room.z as usize][room.y as usize] & 1 << room.x != 0
     }
 
     pub fn get_cell(&self, coord: &Coord) -> Cell {
-        self.validate_coord(coord);
-        self.cells[coord.z as usize][coord.y as usize][coord.x as usize]
+        self.try_get_cell(coord).expect("coordinate in maze")
+    }
+
+    pub fn try_get_cell(&self, coord: &Coord) -> Result<Cell, MazeError> {
+        Self::validate_coord(coord)?;
+        let mut doors = 0;
+        for direction in Direction::ALL {
+            let open = match Self::wall(coord, direction) {
+                Some((plane, room)) => self.has_wall_door(plane, &room),
+                None => self.exit() == Some((*coord, direction)),
+            };
+            if open {
+                doors |= Cell::door_bit(direction);
+            }
+        }
+        Ok(Cell { doors })
+    }
+
+    /// Opens or closes the door leaving `coord` in `direction`, which is also
+    /// the door into `coord` from its neighbour. A maze has one door out, so
+    /// opening one closes any other.
+    pub fn set_door(&mut self, coord: &Coord, direction: Direction, open: bool) {
+        self.try_set_door(coord, direction, open)
+            .expect("coordinate in maze")
+    }
+
+    pub fn try_set_door(
+        &mut self,
+        coord: &Coord,
+        direction: Direction,
+        open: bool,
+    ) -> Result<(), MazeError> {
+        Self::validate_coord(coord)?;
+        match Self::wall(coord, direction) {
+            Some((plane, room)) => {
+                let bits = self.wall_bits_mut(plane, &room);
+                if open {
+                    *bits |= 1 << room.x;
+                } else {
+                    *bits &= !(1 << room.x);
+                }
+            }
+            None if open => self.exit = Some((Self::index_of(coord) as u16, direction)),
+            None if self.exit() == Some((*coord, direction)) => self.exit = None,
+            None => (),
+        }
+        Ok(())
+    }
+
+    pub fn neighbor(coord: Coord, direction: Direction) -> Option<Coord> {
+        let next = coord.move_in_direction(direction);
+        (!Self::is_outside(&next)).then_some(next)
     }
 
-    pub fn get_cell_mut(&mut self, coord: &Coord) -> &mut Cell {
-        self.validate_coord(coord);
-        &mut self.cells[coord.z as usize][coord.y as usize][coord.x as usize]
+    pub fn index_of(coord: &Coord) -> usize {
+        (coord.z as usize * Y + coord.y as usize) * X + coord.x as usize
+    }
+
+    pub fn coord_at(index: usize) -> Coord {
+        Coord {
+            x: (index % X) as isize,
+            y: (index / X % Y) as isize,
+            z: (index / (X * Y)) as isize,
+        }
+    }
+
+    pub fn random_coord<R: Rng>(rng: &mut R) -> Coord {
+        let x = rng.gen_range(0..X) as isize;
+        let y = rng.gen_range(0..Y) as isize;
+        let z = rng.gen_range(0..Z) as isize;
+        Coord { x, y, z }
+    }
+
+    /// Opens the wall between `coord` and its neighbour in `direction`,
+    /// returning the neighbour, or `None` if it is outside the maze.
+    pub fn carve_passage(&mut self, coord: Coord, direction: Direction) -> Option<Coord> {
+        let next = Self::neighbor(coord, direction)?;
+        self.set_door(&coord, direction, true);
+        Some(next)
     }
 
     pub fn is_win(&self, coord: &Coord) -> bool {
+        Self::is_outside(coord)
+    }
+
+    pub fn is_outside(coord: &Coord) -> bool {
         if coord.x < 0 || coord.y < 0 || coord.z < 0 {
             return true;
         }
@@ -228,149 +437,730 @@ impl<const X: usize, const Y: usize, const Z: usize> Maze<X, Y, Z> {
 pub type QuintiMaze = Maze<5, 5, 5>;
 pub const CELL_COUNT: usize = QuintiMaze::cell_count();
 
-#[derive(Default)]
-pub struct MazeGenerator {
-    maze: QuintiMaze,
-    cells: Vec<Coord, CELL_COUNT>,
+/// Fixed capacity list with room for one entry per cell of a `Maze<X, Y, Z>`.
+#[derive(Debug, Clone)]
+pub struct CellList<T, const X: usize, const Y: usize, const Z: usize> {
+    items: [[[T; X]; Y]; Z],
+    len: usize,
 }
 
-impl MazeGenerator {
-    fn get_next_cell_coords(&mut self, coord: Coord, direction: &Direction) -> Option<Coord> {
-        let dimensions = QuintiMaze::dimensions();
-        let deltas = match *direction {
-            Direction::North => (0, -1, 0),
-            Direction::South => (0, 1, 0),
-            Direction::West => (-1, 0, 0),
-            Direction::East => (1, 0, 0),
-            Direction::Up => (0, 0, 1),
-            Direction::Down => (0, 0, -1),
-        };
-        let x = coord.x + deltas.0;
-        if x < 0 || x >= dimensions.0 as isize {
-            return None;
+impl<T: Copy + Default, const X: usize, const Y: usize, const Z: usize> Default
+    for CellList<T, X, Y, Z>
+{
+    fn default() -> Self {
+        Self {
+            items: [[[T::default(); X]; Y]; Z],
+            len: 0,
         }
-        let y = coord.y + deltas.1;
-        if y < 0 || y >= dimensions.1 as isize {
-            return None;
+    }
+}
+
+impl<T: Copy + Default, const X: usize, const Y: usize, const Z: usize> CellList<T, X, Y, Z> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub const fn capacity() -> usize {
+        Maze::<X, Y, Z>::cell_count()
+    }
+
+    pub fn push(&mut self, item: T) -> Result<(), MazeError> {
+        if self.len == Self::capacity() {
+            return Err(MazeError::CapacityExceeded);
         }
-        let z = coord.z + deltas.2;
-        if z < 0 || z >= dimensions.2 as isize {
-            return None;
+        let index = self.len;
+        self.len += 1;
+        self[index] = item;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.last().copied()?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let item = self[index];
+        self.copy_within(index + 1.., index);
+        self.len -= 1;
+        item
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let item = self[index];
+        let last = self.len - 1;
+        self[index] = self[last];
+        self.len = last;
+        item
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<T, const X: usize, const Y: usize, const Z: usize> Deref for CellList<T, X, Y, Z> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items.as_flattened().as_flattened()[..self.len]
+    }
+}
+
+impl<T, const X: usize, const Y: usize, const Z: usize> DerefMut for CellList<T, X, Y, Z> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.items.as_flattened_mut().as_flattened_mut()[..self.len]
+    }
+}
+
+/// One value per cell of a `Maze<X, Y, Z>`, indexed by coordinate.
+#[derive(Debug, Clone)]
+pub struct CellMap<T, const X: usize, const Y: usize, const Z: usize> {
+    values: [[[T; X]; Y]; Z],
+}
+
+impl<T: Copy, const X: usize, const Y: usize, const Z: usize> CellMap<T, X, Y, Z> {
+    pub fn new(value: T) -> Self {
+        Self {
+            values: [[[value; X]; Y]; Z],
         }
-        Some(Coord { x, y, z })
     }
 
-    fn is_cell_visited(&self, coord: Coord) -> bool {
-        self.maze.get_cell(&coord).visited
+    pub fn as_slice(&self) -> &[T] {
+        self.values.as_flattened().as_flattened()
     }
 
-    fn carve_passage(&mut self, coord: Coord, direction: &Direction) -> Option<Coord> {
-        let next = self.get_next_cell_coords(coord, direction)?;
-        let opposite = direction.opposite();
-        let current = self.maze.get_cell_mut(&coord);
-        current.visited = true;
-        current.remove_wall(direction);
-        let next_cell = self.maze.get_cell_mut(&next);
-        next_cell.visited = true;
-        next_cell.remove_wall(&opposite);
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.values.as_flattened_mut().as_flattened_mut()
+    }
+}
 
-        Some(next)
+impl<T: Copy + Default, const X: usize, const Y: usize, const Z: usize> Default
+    for CellMap<T, X, Y, Z>
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const X: usize, const Y: usize, const Z: usize> Index<&Coord> for CellMap<T, X, Y, Z> {
+    type Output = T;
+
+    fn index(&self, coord: &Coord) -> &T {
+        &self.values[coord.z as usize][coord.y as usize][coord.x as usize]
+    }
+}
+
+impl<T, const X: usize, const Y: usize, const Z: usize> IndexMut<&Coord> for CellMap<T, X, Y, Z> {
+    fn index_mut(&mut self, coord: &Coord) -> &mut T {
+        &mut self.values[coord.z as usize][coord.y as usize][coord.x as usize]
+    }
+}
+
+/// Where `MazeGenerator` puts the door out of the maze.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExitPlacement {
+    /// Up through the ceiling of the room in the far top corner.
+    #[default]
+    Corner,
+    /// A random room on the outer face in the given direction.
+    Face(Direction),
+    /// A random room on a random outer face.
+    Random,
+    /// The outer room that takes the most moves to reach from the start.
+    FarthestFromStart,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GenerationOptions {
+    pub exit: ExitPlacement,
+    pub start: Coord,
+    /// When the shortest path from `start` passes through fewer rooms than
+    /// this, the start is moved to a random room that is far enough away.
+    pub min_solution_length: usize,
+}
+
+#[derive(Default)]
+pub struct MazeGenerator<const X: usize, const Y: usize, const Z: usize> {
+    maze: Maze<X, Y, Z>,
+    options: GenerationOptions,
+    start: Coord,
+}
+
+pub type QuintiMazeGenerator = MazeGenerator<5, 5, 5>;
+
+impl<const X: usize, const Y: usize, const Z: usize> MazeGenerator<X, Y, Z> {
+    pub fn new(options: GenerationOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
     }
 
     pub fn generate(&mut self, seed: Option<u64>) {
-        let mut rng = ChaChaRng::seed_from_u64(seed.unwrap_or(12));
-        let (max_x, max_y, max_z) = QuintiMaze::dimensions();
-        let x = rng.gen_range(0..max_x) as isize;
-        let y = rng.gen_range(0..max_y) as isize;
-        let z = rng.gen_range(0..max_z) as isize;
-
-        let mut directions: Vec<Direction, 6> = Default::default();
-        directions.extend([
-            Direction::North,
-            Direction::South,
-            Direction::West,
-            Direction::East,
-            Direction::Up,
-            Direction::Down,
-        ]);
-
-        self.cells.push(Coord { x, y, z }).expect("push");
-
-        while !self.cells.is_empty() {
-            let mut index = Some(rng.gen_range(0..self.cells.len()));
-            let coords = self.cells[index.unwrap_or(0)];
-
-            directions.shuffle(&mut rng);
-            for dir in &directions {
-                let next = match self.get_next_cell_coords(coords, dir) {
-                    Some(next) => next,
-                    None => continue,
-                };
+        self.generate_with(&Algorithm::default(), seed);
+    }
 
-                if self.is_cell_visited(next) {
-                    continue;
-                }
+    pub fn generate_with<A: MazeAlgorithm>(&mut self, algorithm: &A, seed: Option<u64>) {
+        self.try_generate_with(algorithm, seed)
+            .expect("maze generation");
+    }
 
-                if let Some(next) = self.carve_passage(coords, dir) {
-                    self.cells.push(next).expect("push");
-                    index = None;
-                    break;
+    pub fn try_generate_with<A: MazeAlgorithm>(
+        &mut self,
+        algorithm: &A,
+        seed: Option<u64>,
+    ) -> Result<(), MazeError> {
+        let mut rng = ChaChaRng::seed_from_u64(seed.unwrap_or(12));
+        self.maze = Maze::default();
+        algorithm.carve(&mut self.maze, &mut rng)?;
+        Maze::<X, Y, Z>::validate_coord(&self.options.start)?;
+        self.start = self.options.start;
+        self.place_exit(&mut rng)?;
+        self.place_start(&mut rng)
+    }
+
+    fn place_exit<R: Rng>(&mut self, rng: &mut R) -> Result<(), MazeError> {
+        let (max_x, max_y, max_z) = Maze::<X, Y, Z>::dimensions();
+        let (coord, direction) = match self.options.exit {
+            ExitPlacement::Corner => (
+                Coord {
+                    x: max_x as isize - 1,
+                    y: max_y as isize - 1,
+                    z: max_z as isize - 1,
+                },
+                Direction::Up,
+            ),
+            ExitPlacement::Face(direction) => (Self::random_on_face(direction, rng), direction),
+            ExitPlacement::Random => {
+                let direction = *Direction::ALL.choose(rng).expect("direction");
+                (Self::random_on_face(direction, rng), direction)
+            }
+            ExitPlacement::FarthestFromStart => {
+                let distances = try_distances_from(&self.maze, self.start)?;
+                let mut farthest = (self.start, Direction::Up);
+                let mut farthest_distance = 0;
+                for index in 0..Maze::<X, Y, Z>::cell_count() {
+                    let coord = Maze::<X, Y, Z>::coord_at(index);
+                    let outward = Direction::ALL
+                        .into_iter()
+                        .find(|direction| Maze::<X, Y, Z>::neighbor(coord, *direction).is_none());
+                    if let (Some(direction), Some(distance)) = (outward, distances[&coord]) {
+                        if distance > farthest_distance {
+                            farthest = (coord, direction);
+                            farthest_distance = distance;
+                        }
+                    }
                 }
+                farthest
             }
+        };
+        self.maze.try_set_door(&coord, direction, true)
+    }
 
-            if let Some(index) = index {
-                self.cells.remove(index);
+    fn random_on_face<R: Rng>(direction: Direction, rng: &mut R) -> Coord {
+        let (max_x, max_y, max_z) = Maze::<X, Y, Z>::dimensions();
+        let mut coord = Maze::<X, Y, Z>::random_coord(rng);
+        match direction {
+            Direction::North => coord.y = 0,
+            Direction::South => coord.y = max_y as isize - 1,
+            Direction::West => coord.x = 0,
+            Direction::East => coord.x = max_x as isize - 1,
+            Direction::Down => coord.z = 0,
+            Direction::Up => coord.z = max_z as isize - 1,
+        }
+        coord
+    }
+
+    fn place_start<R: Rng>(&mut self, rng: &mut R) -> Result<(), MazeError> {
+        let min_length = self.options.min_solution_length;
+        let distances = try_distances_to_exit(&self.maze)?;
+        let length = |coord: &Coord| distances[coord].unwrap_or_default() as usize;
+        if length(&self.start) >= min_length {
+            return Ok(());
+        }
+
+        let mut candidates = CellList::<Coord, X, Y, Z>::new();
+        let mut farthest = self.start;
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            let coord = Maze::<X, Y, Z>::coord_at(index);
+            if length(&coord) >= min_length {
+                candidates.push(coord)?;
+            }
+            if length(&coord) > length(&farthest) {
+                farthest = coord;
             }
         }
-        self.maze.cells[max_x - 1][max_z - 1][max_z - 1].remove_wall(&Direction::Up);
+        self.start = candidates.choose(rng).copied().unwrap_or(farthest);
+        Ok(())
+    }
+
+    /// Where the player should begin, set by the last call to `generate`.
+    pub fn start(&self) -> Coord {
+        self.start
+    }
+
+    pub fn maze(&self) -> &Maze<X, Y, Z> {
+        &self.maze
     }
 
-    pub fn take(self) -> QuintiMaze {
+    pub fn take(self) -> Maze<X, Y, Z> {
         self.maze
     }
 }
 
-pub type SolutionPath = Deque<Coord, CELL_COUNT>;
+/// Path from a starting room to the exit, stored with the starting room at
+/// the back and the position just outside the maze at the front.
+///
+/// A path can visit every room of the maze and then step outside, so the
+/// exit position is kept apart from the rooms.
+#[derive(Debug, Default, Clone)]
+pub struct SolutionPath<const X: usize, const Y: usize, const Z: usize> {
+    exit: Option<Coord>,
+    rooms: CellList<Coord, X, Y, Z>,
+}
 
-fn find_exit(
-    maze: &QuintiMaze,
-    prior_location: Option<Coord>,
-    location: Coord,
-    result: &mut SolutionPath,
-) -> bool {
-    for direction in [
-        Direction::Up,
-        Direction::Down,
-        Direction::West,
-        Direction::East,
-        Direction::South,
-        Direction::North,
-    ] {
-        let cell = maze.get_cell(&location);
-        if cell.has_door(direction) {
-            let new_location = location.move_in_direction(direction);
-            if maze.is_win(&new_location) {
-                result.push_back(new_location).expect("push");
-                result.push_back(location).expect("push");
-                return true;
+pub type QuintiSolutionPath = SolutionPath<5, 5, 5>;
+
+impl<const X: usize, const Y: usize, const Z: usize> SolutionPath<X, Y, Z> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rooms.len() + usize::from(self.exit.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push_back(&mut self, coord: Coord) -> Result<(), MazeError> {
+        if self.is_empty() && Maze::<X, Y, Z>::is_outside(&coord) {
+            self.exit = Some(coord);
+            Ok(())
+        } else {
+            self.rooms.push(coord)
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<Coord> {
+        self.rooms.pop().or_else(|| self.exit.take())
+    }
+
+    pub fn front(&self) -> Option<&Coord> {
+        self.exit.as_ref().or_else(|| self.rooms.first())
+    }
+
+    pub fn back(&self) -> Option<&Coord> {
+        self.rooms.last().or(self.exit.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Coord> {
+        self.exit.iter().chain(self.rooms.iter())
+    }
+}
+
+/// Number of moves from each room to the outside of the maze, or to some
+/// other target, with `None` for rooms that can't get there.
+pub type DistanceMap<const X: usize, const Y: usize, const Z: usize> =
+    CellMap<Option<u16>, X, Y, Z>;
+
+const SEARCH_ORDER: [Direction; 6] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::West,
+    Direction::East,
+    Direction::South,
+    Direction::North,
+];
+
+/// Breadth first search outward from every exit door.
+pub fn distances_to_exit<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+) -> DistanceMap<X, Y, Z> {
+    try_distances_to_exit(maze).expect("distances to exit")
+}
+
+pub fn try_distances_to_exit<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+) -> Result<DistanceMap<X, Y, Z>, MazeError> {
+    let mut distances = DistanceMap::new(None);
+    let mut queue = CellList::<Coord, X, Y, Z>::new();
+
+    for index in 0..Maze::<X, Y, Z>::cell_count() {
+        let coord = Maze::<X, Y, Z>::coord_at(index);
+        let cell = maze.get_cell(&coord);
+        let is_exit = SEARCH_ORDER.iter().any(|direction| {
+            cell.has_door(*direction) && maze.is_win(&coord.move_in_direction(*direction))
+        });
+        if is_exit {
+            distances[&coord] = Some(1);
+            queue.push(coord)?;
+        }
+    }
+
+    spread_distances(maze, &mut distances, &mut queue, true)?;
+    Ok(distances)
+}
+
+/// Breadth first search outward from `start`, counting the moves needed to
+/// reach every room.
+pub fn distances_from<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    start: Coord,
+) -> DistanceMap<X, Y, Z> {
+    try_distances_from(maze, start).expect("distances from start")
+}
+
+pub fn try_distances_from<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    start: Coord,
+) -> Result<DistanceMap<X, Y, Z>, MazeError> {
+    Maze::<X, Y, Z>::validate_coord(&start)?;
+    let mut distances = DistanceMap::new(None);
+    let mut queue = CellList::<Coord, X, Y, Z>::new();
+    distances[&start] = Some(0);
+    queue.push(start)?;
+    spread_distances(maze, &mut distances, &mut queue, false)?;
+    Ok(distances)
+}
+
+fn spread_distances<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    distances: &mut DistanceMap<X, Y, Z>,
+    queue: &mut CellList<Coord, X, Y, Z>,
+    toward_queued: bool,
+) -> Result<(), MazeError> {
+    // Every room is queued at most once, so the queue never needs to wrap.
+    let mut head = 0;
+    while head < queue.len() {
+        let coord = queue[head];
+        head += 1;
+        let distance = distances[&coord].unwrap_or_default() + 1;
+        for direction in SEARCH_ORDER {
+            let next = match Maze::<X, Y, Z>::neighbor(coord, direction) {
+                Some(next) => next,
+                None => continue,
+            };
+            let connected = if toward_queued {
+                maze.get_cell(&next).has_door(direction.opposite())
+            } else {
+                maze.get_cell(&coord).has_door(direction)
+            };
+            if distances[&next].is_none() && connected {
+                distances[&next] = Some(distance);
+                queue.push(next)?;
             }
-            if Some(new_location) != prior_location
-                && find_exit(maze, Some(location), new_location, result)
-            {
-                result.push_back(location).expect("push");
-                return true;
+        }
+    }
+    Ok(())
+}
+
+/// The way out of `coord` that leads one step closer to the exit.
+pub fn direction_to_exit<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    distances: &DistanceMap<X, Y, Z>,
+    coord: Coord,
+) -> Option<Direction> {
+    Maze::<X, Y, Z>::validate_coord(&coord).ok()?;
+    let distance = distances[&coord]?;
+    let cell = maze.get_cell(&coord);
+    SEARCH_ORDER.into_iter().find(|direction| {
+        if !cell.has_door(*direction) {
+            return false;
+        }
+        let next = coord.move_in_direction(*direction);
+        if maze.is_win(&next) {
+            distance == 1
+        } else {
+            distances[&next] == Some(distance - 1)
+        }
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution<const X: usize, const Y: usize, const Z: usize> {
+    pub distances: DistanceMap<X, Y, Z>,
+    pub path: Option<SolutionPath<X, Y, Z>>,
+}
+
+pub type QuintiSolution = Solution<5, 5, 5>;
+
+/// Finds a shortest path from `starting_position` to the exit, along with the
+/// distance to the exit from every room.
+pub fn solve<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    starting_position: Coord,
+) -> Solution<X, Y, Z> {
+    try_solve(maze, starting_position).expect("solve")
+}
+
+pub fn try_solve<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    starting_position: Coord,
+) -> Result<Solution<X, Y, Z>, MazeError> {
+    Maze::<X, Y, Z>::validate_coord(&starting_position)?;
+    let distances = try_distances_to_exit(maze)?;
+    let mut rooms = CellList::<Coord, X, Y, Z>::new();
+    let mut path = None;
+
+    let mut location = starting_position;
+    while let Some(direction) = direction_to_exit(maze, &distances, location) {
+        rooms.push(location)?;
+        location = location.move_in_direction(direction);
+        if maze.is_win(&location) {
+            let mut solution_path = SolutionPath::new();
+            solution_path.push_back(location)?;
+            for room in rooms.iter().rev() {
+                solution_path.push_back(*room)?;
+            }
+            path = Some(solution_path);
+            break;
+        }
+    }
+
+    Ok(Solution { distances, path })
+}
+
+pub fn find_path_to_exit<const X: usize, const Y: usize, const Z: usize>(
+    maze: &Maze<X, Y, Z>,
+    starting_position: Coord,
+) -> (bool, SolutionPath<X, Y, Z>) {
+    match solve(maze, starting_position).path {
+        Some(path) => (true, path),
+        None => (false, SolutionPath::new()),
+    }
+}
+
+/// Something wrong with a maze, found by `Maze::check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// The row starting at this room has bits set that aren't walls between
+    /// rooms.
+    UnusedBits(Coord),
+    /// Nothing leads out of the maze.
+    NoExit,
+    /// The room can't be reached from the exit.
+    Unreachable(Coord),
+    /// A passage that closes a loop, so the maze isn't perfect.
+    Cycle(Coord, Direction),
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> Maze<X, Y, Z> {
+    /// Calls `report` with every violation found, and checks for loops only
+    /// when `perfect` is set.
+    pub fn check(&self, perfect: bool, mut report: impl FnMut(Violation)) {
+        for row in self.rows_with_unused_bits() {
+            report(Violation::UnusedBits(row));
+        }
+
+        let distances = match self.exit {
+            Some(_) => distances_to_exit(self),
+            None => {
+                report(Violation::NoExit);
+                distances_from(self, Coord::default())
+            }
+        };
+        for index in 0..Self::cell_count() {
+            let coord = Self::coord_at(index);
+            if distances[&coord].is_none() {
+                report(Violation::Unreachable(coord));
+            }
+        }
+
+        if perfect {
+            let mut sets = CellMap::<usize, X, Y, Z>::new(0);
+            let sets = sets.as_mut_slice();
+            for (index, set) in sets.iter_mut().enumerate() {
+                *set = index;
+            }
+            for index in 0..Self::cell_count() {
+                let coord = Self::coord_at(index);
+                let cell = self.get_cell(&coord);
+                for direction in Self::PASSAGES {
+                    let next = match Self::neighbor(coord, direction) {
+                        Some(next) if cell.has_door(direction) => next,
+                        _ => continue,
+                    };
+                    let set = root(sets, index);
+                    let next_set = root(sets, Self::index_of(&next));
+                    if set == next_set {
+                        report(Violation::Cycle(coord, direction));
+                    } else {
+                        sets[next_set] = set;
+                    }
+                }
             }
         }
     }
-    false
+
+    /// True if `check` finds nothing wrong.
+    pub fn is_valid(&self, perfect: bool) -> bool {
+        let mut valid = true;
+        self.check(perfect, |_| valid = false);
+        valid
+    }
 }
 
-pub fn find_path_to_exit(maze: &QuintiMaze, starting_position: Coord) -> (bool, SolutionPath) {
-    let mut vec = SolutionPath::new();
+/// The set `index` is in, following `sets` to the room that stands for it.
+/// Kept apart from the generators' own sets so that `check` doesn't trust
+/// them.
+fn root(sets: &[usize], mut index: usize) -> usize {
+    while sets[index] != index {
+        index = sets[index];
+    }
+    index
+}
 
-    let result = find_exit(maze, None, starting_position, &mut vec);
+/// Where the player was when the 1982 program saved an "OLD MAZE" file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OldMazePlayer {
+    pub position: Coord,
+    pub time: u32,
+    pub facing: Direction,
+}
 
-    (result, vec)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OldMazeError {
+    /// The file ended before the given line, counting from one.
+    MissingLine(usize),
+    /// The room string on the given line isn't 30 "0" or "X" characters.
+    BadRooms(usize),
+    /// The number on the given line isn't valid.
+    BadNumber(usize),
+}
+
+/// Longest text `write_old_maze` produces.
+pub const OLD_MAZE_MAX_LEN: usize = 1024;
+
+// The BASIC program kept each room as six characters in this order, using
+// "0" for a door and "X" for a wall.
+const OLD_MAZE_DOORS: [Direction; 6] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
+// FC, the facing, numbered from one.
+const OLD_MAZE_FACINGS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
+/// Reads an "OLD MAZE" file saved by lines 1300-1350 of `quinti-maze.bas`.
+///
+/// The file holds one line of `S$(X,A)` for each floor A and column X, each
+/// with six door characters for every Y, followed by X, Y, A, T and FC. All
+/// coordinates are one based. A door is open if the room on either side
+/// lists it.
+pub fn read_old_maze(text: &str) -> Result<(QuintiMaze, OldMazePlayer), OldMazeError> {
+    let mut lines = text
+        .split(['\r', '\n'])
+        .filter(|line| !line.is_empty())
+        .map(str::trim);
+    let mut next_line = |number: usize| lines.next().ok_or(OldMazeError::MissingLine(number));
+
+    let mut maze = QuintiMaze::default();
+    let (max_x, max_y, max_z) = QuintiMaze::dimensions();
+    let mut number = 0;
+    for z in 0..max_z {
+        for x in 0..max_x {
+            number += 1;
+            let rooms = next_line(number)?.as_bytes();
+            if rooms.len() != max_y * OLD_MAZE_DOORS.len() {
+                return Err(OldMazeError::BadRooms(number));
+            }
+            for (index, door) in rooms.iter().enumerate() {
+                let coord = Coord {
+                    x: x as isize,
+                    y: (index / OLD_MAZE_DOORS.len()) as isize,
+                    z: z as isize,
+                };
+                match door {
+                    b'0' => {
+                        maze.set_door(&coord, OLD_MAZE_DOORS[index % OLD_MAZE_DOORS.len()], true)
+                    }
+                    b'X' => (),
+                    _ => return Err(OldMazeError::BadRooms(number)),
+                }
+            }
+        }
+    }
+
+    let mut read_number = |max: u32| {
+        number += 1;
+        next_line(number)?
+            .parse::<u32>()
+            .ok()
+            .filter(|value| *value <= max)
+            .ok_or(OldMazeError::BadNumber(number))
+    };
+    let x = read_number(max_x as u32)?;
+    let y = read_number(max_y as u32)?;
+    let z = read_number(max_z as u32)?;
+    let time = read_number(u32::MAX)?;
+    let facing = read_number(OLD_MAZE_FACINGS.len() as u32)?;
+    if x == 0 || y == 0 || z == 0 || facing == 0 {
+        return Err(OldMazeError::BadNumber(number));
+    }
+
+    let player = OldMazePlayer {
+        position: Coord {
+            x: x as isize - 1,
+            y: y as isize - 1,
+            z: z as isize - 1,
+        },
+        time,
+        facing: OLD_MAZE_FACINGS[facing as usize - 1],
+    };
+    Ok((maze, player))
+}
+
+/// Writes `maze` and `player` in the form `read_old_maze` reads, with the
+/// carriage return line endings Apple DOS uses. Facing up or down can't be
+/// written.
+pub fn write_old_maze<W: fmt::Write>(
+    maze: &QuintiMaze,
+    player: &OldMazePlayer,
+    out: &mut W,
+) -> fmt::Result {
+    let (max_x, max_y, max_z) = QuintiMaze::dimensions();
+    for z in 0..max_z {
+        for x in 0..max_x {
+            for y in 0..max_y {
+                let cell = maze.get_cell(&Coord {
+                    x: x as isize,
+                    y: y as isize,
+                    z: z as isize,
+                });
+                for direction in OLD_MAZE_DOORS {
+                    out.write_char(if cell.has_door(direction) { '0' } else { 'X' })?;
+                }
+            }
+            out.write_char('\r')?;
+        }
+    }
+
+    let facing = OLD_MAZE_FACINGS
+        .iter()
+        .position(|facing| *facing == player.facing)
+        .ok_or(fmt::Error)?;
+    write!(
+        out,
+        "{}\r{}\r{}\r{}\r{}\r",
+        player.position.x + 1,
+        player.position.y + 1,
+        player.position.z + 1,
+        player.time,
+        facing + 1
+    )
 }
 
 #[cfg(test)]
@@ -379,14 +1169,15 @@ mod test {
 
     #[test]
     fn test_generate() {
-        let mut generator = MazeGenerator::default();
+        let mut generator = QuintiMazeGenerator::default();
 
         generator.generate(None);
+        assert!(generator.take().is_valid(true));
     }
 
     #[test]
     fn test_solve() {
-        let mut generator = MazeGenerator::default();
+        let mut generator = QuintiMazeGenerator::default();
 
         generator.generate(None);
 
@@ -399,7 +1190,7 @@ mod test {
         assert_eq!(path.pop_back(), Some(Coord { x: 0, y: 0, z: 0 }));
         assert_eq!(path.front().unwrap(), &Coord { x: 4, y: 4, z: 5 });
 
-        let mut generator = MazeGenerator::default();
+        let mut generator = QuintiMazeGenerator::default();
 
         generator.generate(Some(13));
 
@@ -412,4 +1203,303 @@ mod test {
         assert_eq!(path.pop_back(), Some(Coord { x: 0, y: 0, z: 0 }));
         assert_eq!(path.front(), Some(&Coord { x: 4, y: 4, z: 5 }));
     }
+
+    #[test]
+    fn test_other_dimensions() {
+        let mut generator = MazeGenerator::<2, 2, 2>::default();
+        generator.generate(None);
+        let maze = generator.take();
+        let (found, mut path) = find_path_to_exit(&maze, Coord::default());
+        assert!(found);
+        assert_eq!(path.pop_back(), Some(Coord::default()));
+        assert_eq!(path.front(), Some(&Coord { x: 1, y: 1, z: 2 }));
+
+        let mut generator = MazeGenerator::<4, 3, 2>::default();
+        generator.generate(Some(7));
+        let maze = generator.take();
+        let (found, path) = find_path_to_exit(&maze, Coord::default());
+        assert!(found);
+        assert_eq!(path.front(), Some(&Coord { x: 3, y: 2, z: 2 }));
+
+        let mut generator = MazeGenerator::<8, 8, 8>::default();
+        generator.generate(Some(8));
+        let maze = generator.take();
+        let (found, path) = find_path_to_exit(&maze, Coord::default());
+        assert!(found);
+        assert!(path.len() <= CellList::<Coord, 8, 8, 8>::capacity() + 1);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        // A 3x3 floor with every wall open has many paths to the exit.
+        let mut maze = Maze::<3, 3, 1>::default();
+        for index in 0..9 {
+            let coord = Maze::<3, 3, 1>::coord_at(index);
+            maze.carve_passage(coord, Direction::East);
+            maze.carve_passage(coord, Direction::South);
+        }
+        maze.set_door(&Coord { x: 2, y: 2, z: 0 }, Direction::East, true);
+
+        let solution = solve(&maze, Coord::default());
+        let mut path = solution.path.expect("path");
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.front(), Some(&Coord { x: 3, y: 2, z: 0 }));
+        assert_eq!(path.pop_back(), Some(Coord::default()));
+        assert_eq!(solution.distances[&Coord::default()], Some(5));
+        assert_eq!(solution.distances[&Coord { x: 2, y: 2, z: 0 }], Some(1));
+        assert_eq!(solution.distances[&Coord { x: 1, y: 1, z: 0 }], Some(3));
+
+        // Nothing reaches the exit from a sealed room.
+        let mut maze = Maze::<2, 1, 1>::default();
+        maze.set_door(&Coord::default(), Direction::West, true);
+        let solution = solve(&maze, Coord { x: 1, y: 0, z: 0 });
+        assert!(solution.path.is_none());
+        assert_eq!(solution.distances[&Coord { x: 1, y: 0, z: 0 }], None);
+    }
+
+    #[test]
+    fn test_exit_placement() {
+        let mut generator = QuintiMazeGenerator::new(GenerationOptions {
+            exit: ExitPlacement::Face(Direction::West),
+            ..Default::default()
+        });
+        generator.generate(Some(3));
+        let maze = generator.take();
+        let (found, path) = find_path_to_exit(&maze, Coord::default());
+        assert!(found);
+        assert_eq!(path.front().map(|exit| exit.x), Some(-1));
+
+        let mut generator = QuintiMazeGenerator::new(GenerationOptions {
+            exit: ExitPlacement::FarthestFromStart,
+            ..Default::default()
+        });
+        generator.generate(Some(3));
+        let maze = generator.take();
+        let distances = distances_from(&maze, Coord::default());
+        let (found, path) = find_path_to_exit(&maze, Coord::default());
+        assert!(found);
+        let exit_room = path.iter().nth(1).expect("exit room");
+        for index in 0..CELL_COUNT {
+            let coord = QuintiMaze::coord_at(index);
+            if [0, 4].contains(&coord.x) || [0, 4].contains(&coord.y) || [0, 4].contains(&coord.z) {
+                assert!(distances[&coord] <= distances[exit_room]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_solution_length() {
+        for seed in 0..8 {
+            let mut generator = QuintiMazeGenerator::new(GenerationOptions {
+                exit: ExitPlacement::Random,
+                min_solution_length: 15,
+                ..Default::default()
+            });
+            generator.generate(Some(seed));
+            let start = generator.start();
+            let maze = generator.take();
+            let (found, path) = find_path_to_exit(&maze, start);
+            assert!(found);
+            // The path also holds the step outside the maze. When no room is
+            // far enough away the start should be as far as possible.
+            let distances = distances_to_exit(&maze);
+            let longest = distances.as_slice().iter().flatten().max().copied();
+            assert!(path.len() > 15 || distances[&start] == longest);
+        }
+    }
+
+    #[test]
+    fn test_packed_doors() {
+        assert_eq!(core::mem::size_of::<Maze<8, 8, 8>>(), 3 * 8 * 8 + 4);
+
+        let mut maze = Maze::<2, 2, 2>::default();
+        let origin = Coord::default();
+        let east = Coord { x: 1, y: 0, z: 0 };
+        let above = Coord { x: 0, y: 0, z: 1 };
+
+        maze.set_door(&east, Direction::West, true);
+        maze.set_door(&origin, Direction::Up, true);
+        maze.set_door(&origin, Direction::North, true);
+        assert!(maze.get_cell(&origin).has_door(Direction::East));
+        assert!(maze.get_cell(&east).has_door(Direction::West));
+        assert!(maze.get_cell(&above).has_door(Direction::Down));
+        assert!(maze.get_cell(&origin).has_door(Direction::North));
+        assert!(!maze.get_cell(&east).has_door(Direction::North));
+        assert_eq!(maze.exit(), Some((origin, Direction::North)));
+
+        // Opening another door out closes the first.
+        maze.set_door(&above, Direction::West, true);
+        assert!(!maze.get_cell(&origin).has_door(Direction::North));
+        assert_eq!(maze.exit(), Some((above, Direction::West)));
+        maze.set_door(&origin, Direction::North, false);
+        assert_eq!(maze.exit(), Some((above, Direction::West)));
+
+        maze.set_door(&above, Direction::Down, false);
+        assert!(!maze.get_cell(&origin).top());
+        assert!(!maze.get_cell(&above).bottom());
+
+        assert_eq!(
+            Maze::from_bytes(maze.as_bytes(), maze.exit()),
+            Some(maze.clone())
+        );
+        let mut bytes = [0; 12];
+        bytes.copy_from_slice(maze.as_bytes());
+        // The east bit of the last column, the south bits of the last row,
+        // the up bits of the top floor and the bits past the last column
+        // aren't walls between rooms.
+        for (index, bit) in [(0, 0b10), (5, 0b1), (10, 0b1), (0, 0b100), (9, 0b1000_0000)] {
+            let mut bad = bytes;
+            bad[index] |= bit;
+            assert_eq!(Maze::<2, 2, 2>::from_bytes(&bad, maze.exit()), None);
+        }
+        assert_eq!(Maze::<2, 2, 2>::from_bytes(&bytes[1..], maze.exit()), None);
+        for exit in [
+            (origin, Direction::East),
+            (Coord { x: 2, y: 0, z: 0 }, Direction::East),
+        ] {
+            assert_eq!(Maze::<2, 2, 2>::from_bytes(&bytes, Some(exit)), None);
+        }
+    }
+
+    #[test]
+    fn test_maze_errors() {
+        let mut maze = QuintiMaze::default();
+        let origin = Coord::default();
+        let outside = Coord { x: 5, y: 0, z: 0 };
+        let far = Coord { x: 2, y: 0, z: 0 };
+
+        assert_eq!(
+            maze.try_get_cell(&outside).err(),
+            Some(MazeError::OutOfBounds(outside))
+        );
+        assert_eq!(
+            maze.try_set_door(&outside, Direction::Up, true),
+            Err(MazeError::OutOfBounds(outside))
+        );
+        assert_eq!(
+            origin.try_direction_to(far),
+            Err(MazeError::NotAdjacent(origin, far))
+        );
+        assert_eq!(
+            origin.try_direction_to(origin),
+            Err(MazeError::NotAdjacent(origin, origin))
+        );
+        assert_eq!(
+            origin.try_direction_to(Coord { x: 0, y: 0, z: -1 }),
+            Ok(Direction::Down)
+        );
+        assert_eq!(
+            try_solve(&maze, outside).err(),
+            Some(MazeError::OutOfBounds(outside))
+        );
+
+        let mut list = CellList::<Coord, 1, 1, 2>::new();
+        assert_eq!(list.push(origin), Ok(()));
+        assert_eq!(list.push(origin), Ok(()));
+        assert_eq!(list.push(origin), Err(MazeError::CapacityExceeded));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut generator = MazeGenerator::<3, 3, 2>::default();
+        generator.generate(Some(9));
+        assert!(generator.take().is_valid(true));
+
+        let mut violations = heapless::Vec::<Violation, 32>::new();
+        Maze::<3, 3, 2>::default().check(true, |violation| {
+            violations.push(violation).expect("push");
+        });
+        assert_eq!(violations[0], Violation::NoExit);
+        assert_eq!(violations.len(), 3 * 3 * 2);
+
+        // A loop around all four rooms, with the exit north.
+        let mut maze = Maze::<2, 2, 1>::default();
+        let origin = Coord::default();
+        for (coord, direction) in [
+            (origin, Direction::East),
+            (origin, Direction::South),
+            (Coord { x: 1, y: 0, z: 0 }, Direction::South),
+            (Coord { x: 0, y: 1, z: 0 }, Direction::East),
+        ] {
+            maze.carve_passage(coord, direction);
+        }
+        maze.set_door(&origin, Direction::North, true);
+        assert!(maze.is_valid(false));
+        violations.clear();
+        maze.check(true, |violation| violations.push(violation).expect("push"));
+        assert_eq!(
+            violations.as_slice(),
+            [Violation::Cycle(
+                Coord { x: 0, y: 1, z: 0 },
+                Direction::East
+            )]
+        );
+        maze.set_door(&origin, Direction::East, false);
+        assert!(maze.is_valid(true));
+
+        // A door east of the last room, which `from_bytes` wouldn't allow,
+        // and nothing leading out.
+        let maze = Maze::<2, 1, 1> {
+            walls: [[[0b11]], [[0]], [[0]]],
+            exit: None,
+        };
+        violations.clear();
+        maze.check(true, |violation| violations.push(violation).expect("push"));
+        assert_eq!(
+            violations.as_slice(),
+            [Violation::UnusedBits(origin), Violation::NoExit]
+        );
+    }
+
+    #[test]
+    fn test_old_maze() {
+        let mut generator = QuintiMazeGenerator::default();
+        generator.generate(Some(1982));
+        let maze = generator.take();
+        let player = OldMazePlayer {
+            position: Coord { x: 1, y: 2, z: 3 },
+            time: 47,
+            facing: Direction::East,
+        };
+
+        let mut text = heapless::String::<OLD_MAZE_MAX_LEN>::new();
+        write_old_maze(&maze, &player, &mut text).expect("write");
+        assert!(text.ends_with("\r2\r3\r4\r47\r3\r"));
+        assert_eq!(read_old_maze(&text), Ok((maze, player)));
+    }
+
+    #[test]
+    fn test_old_maze_doors() {
+        // The exit is the up door of X=5, Y=5, A=5, in the last string.
+        let mut text = heapless::String::<OLD_MAZE_MAX_LEN>::new();
+        for line in 0..25 {
+            let mut rooms = [b'X'; 30];
+            match line {
+                // East from X=1 and west from X=2, both at Y=1, A=1.
+                0 => rooms[4] = b'0',
+                1 => rooms[5] = b'0',
+                24 => rooms[24] = b'0',
+                _ => (),
+            }
+            text.push_str(core::str::from_utf8(&rooms).unwrap())
+                .unwrap();
+            text.push_str("\r\n").unwrap();
+        }
+        text.push_str("1\n1\n1\n0\n1\n").unwrap();
+
+        let (maze, player) = read_old_maze(&text).expect("read");
+        assert_eq!(player.position, Coord::default());
+        assert_eq!(player.facing, Direction::North);
+        assert!(maze.get_cell(&Coord::default()).has_door(Direction::East));
+        assert!(maze
+            .get_cell(&Coord { x: 4, y: 4, z: 4 })
+            .has_door(Direction::Up));
+        assert!(!maze.get_cell(&Coord::default()).has_door(Direction::Up));
+
+        assert_eq!(
+            read_old_maze(&text[..text.len() - 2]),
+            Err(OldMazeError::MissingLine(30))
+        );
+        assert_eq!(read_old_maze("0X0"), Err(OldMazeError::BadRooms(1)));
+    }
 }
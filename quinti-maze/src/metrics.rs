@@ -0,0 +1,359 @@
+use crate::{
+    algorithms::Algorithm,
+    maze::{
+        direction_to_exit, try_distances_to_exit, Coord, Direction, Maze, MazeError, MazeGenerator,
+    },
+};
+
+/// Measurements of how hard a maze is to get out of from a given start.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MazeMetrics {
+    /// Moves on the shortest way out, or `None` if there isn't one.
+    pub solution_length: Option<usize>,
+    /// Rooms with a single door, other than the exit room.
+    pub dead_ends: usize,
+    /// Rooms with three or more doors.
+    pub junctions: usize,
+    /// The share of moves on the shortest way out that go up or down.
+    pub vertical_ratio: f32,
+    /// Average number of passages between dead ends, junctions and the exit.
+    pub average_corridor: f32,
+    /// Average number of rooms in a dead end branch before it reaches a
+    /// junction. Mazes with a high river factor have few, long dead ends.
+    pub river: f32,
+    /// Rooms in the maze, which the score is rated against.
+    pub rooms: usize,
+}
+
+/// How hard a maze is, as offered to the player.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| *difficulty as u8 == index)
+    }
+
+    /// Rates a difficulty score from `MazeMetrics::score` for a maze made by
+    /// `algorithm` with `rooms` rooms, using the thresholds of the nearest
+    /// calibrated size.
+    pub fn from_score(score: f32, algorithm: Algorithm, rooms: usize) -> Self {
+        let size = CALIBRATED_ROOMS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, calibrated)| calibrated.abs_diff(rooms))
+            .map_or(0, |(size, _)| size);
+        let (easy_below, hard_from) = score_thresholds(algorithm)[size];
+        if score < easy_below {
+            Self::Easy
+        } else if score < hard_from {
+            Self::Medium
+        } else {
+            Self::Hard
+        }
+    }
+}
+
+/// The rooms in the sizes of maze the game plays, which the score thresholds
+/// are calibrated for.
+const CALIBRATED_ROOMS: [usize; 3] = [3 * 3 * 3, 5 * 5 * 5, 7 * 7 * 7];
+
+/// The score below which a maze is Easy and the score from which it's Hard,
+/// for each size in `CALIBRATED_ROOMS`. Scores grow with size, and each
+/// algorithm makes mazes of its own shape, so every pair splits 600 mazes of
+/// that algorithm and size, from seeds 1000 to 1599 played from the default
+/// start, as near into thirds as ties allow. `test_thresholds` checks them on
+/// other seeds.
+fn score_thresholds(algorithm: Algorithm) -> [(f32, f32); 3] {
+    match algorithm {
+        // Most small mazes score between 11.6 and 12.1, so the split is narrow.
+        Algorithm::GrowingTree => [(11.75, 11.95), (26.15, 28.05), (48.45, 51.05)],
+        Algorithm::RecursiveBacktracker => [(13.35, 19.15), (40.95, 66.85), (97.55, 173.9)],
+        Algorithm::Kruskal => [(11.75, 13.75), (29.45, 35.55), (57.45, 66.25)],
+        Algorithm::Prim => [(11.65, 11.95), (26.15, 29.45), (49.65, 52.55)],
+        Algorithm::Wilson => [(11.75, 13.85), (30.85, 37.85), (58.45, 70.6)],
+        Algorithm::AldousBroder => [(11.75, 15.25), (30.85, 38.05), (60.55, 72.25)],
+        Algorithm::Eller => [(11.75, 15.25), (29.15, 34.4), (53.35, 60.35)],
+    }
+}
+
+impl From<Difficulty> for &'static str {
+    fn from(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+impl MazeMetrics {
+    pub fn measure<const X: usize, const Y: usize, const Z: usize>(
+        maze: &Maze<X, Y, Z>,
+        start: Coord,
+    ) -> Result<Self, MazeError> {
+        Maze::<X, Y, Z>::validate_coord(&start)?;
+        let distances = try_distances_to_exit(maze)?;
+        let mut metrics = Self {
+            solution_length: distances[&start].map(|distance| distance as usize),
+            rooms: Maze::<X, Y, Z>::cell_count(),
+            ..Default::default()
+        };
+
+        let door_count = |coord: &Coord| {
+            let cell = maze.get_cell(coord);
+            Direction::ALL
+                .iter()
+                .filter(|direction| cell.has_door(**direction))
+                .count()
+        };
+
+        // Every passage is counted from both of its rooms, and the exit door
+        // from its one room.
+        let mut doors = 0;
+        let mut ends = 0;
+        let mut dead_end_rooms = 0;
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            let coord = Maze::<X, Y, Z>::coord_at(index);
+            let count = door_count(&coord);
+            doors += count;
+            if count != 2 {
+                ends += count;
+            }
+            if count >= 3 {
+                metrics.junctions += 1;
+            }
+            if count == 1 && distances[&coord] != Some(1) {
+                metrics.dead_ends += 1;
+                dead_end_rooms += Self::branch_length(maze, coord, door_count);
+            }
+        }
+
+        let exits = Self::count_exits(maze);
+        let passages = (doors - exits) / 2 + exits;
+        let corridors = (ends + exits) / 2;
+        if corridors > 0 {
+            metrics.average_corridor = passages as f32 / corridors as f32;
+        }
+        if metrics.dead_ends > 0 {
+            metrics.river = dead_end_rooms as f32 / metrics.dead_ends as f32;
+        }
+
+        if let Some(length) = metrics.solution_length.filter(|length| *length > 0) {
+            let mut vertical = 0;
+            let mut coord = start;
+            while let Some(direction) = direction_to_exit(maze, &distances, coord) {
+                if matches!(direction, Direction::Up | Direction::Down) {
+                    vertical += 1;
+                }
+                coord = coord.move_in_direction(direction);
+            }
+            metrics.vertical_ratio = vertical as f32 / length as f32;
+        }
+
+        Ok(metrics)
+    }
+
+    /// Walks from a dead end until it reaches a junction or the exit,
+    /// counting the rooms on the way.
+    fn branch_length<const X: usize, const Y: usize, const Z: usize>(
+        maze: &Maze<X, Y, Z>,
+        dead_end: Coord,
+        door_count: impl Fn(&Coord) -> usize,
+    ) -> usize {
+        let mut length = 1;
+        let mut previous = dead_end;
+        let mut coord = dead_end;
+        loop {
+            let cell = maze.get_cell(&coord);
+            let next = Direction::ALL
+                .into_iter()
+                .filter(|direction| cell.has_door(*direction))
+                .map(|direction| coord.move_in_direction(direction))
+                .find(|next| *next != previous);
+            match next {
+                Some(next) if !maze.is_win(&next) && door_count(&next) == 2 => {
+                    previous = coord;
+                    coord = next;
+                    length += 1;
+                }
+                _ => return length,
+            }
+        }
+    }
+
+    fn count_exits<const X: usize, const Y: usize, const Z: usize>(maze: &Maze<X, Y, Z>) -> usize {
+        let mut exits = 0;
+        for index in 0..Maze::<X, Y, Z>::cell_count() {
+            let coord = Maze::<X, Y, Z>::coord_at(index);
+            let cell = maze.get_cell(&coord);
+            exits += Direction::ALL
+                .iter()
+                .filter(|direction| {
+                    cell.has_door(**direction)
+                        && Maze::<X, Y, Z>::neighbor(coord, **direction).is_none()
+                })
+                .count();
+        }
+        exits
+    }
+
+    /// A single number for how hard the maze is. Moves up and down count
+    /// extra, as they're the easiest to lose track of, and rooms hidden in
+    /// long dead ends add to the time spent wandering.
+    pub fn score(&self) -> f32 {
+        let length = match self.solution_length {
+            Some(length) => length as f32,
+            None => return f32::INFINITY,
+        };
+        length * (1.0 + self.vertical_ratio) + self.dead_ends as f32 * self.river / 10.0
+    }
+
+    /// How hard the maze is for one made by `algorithm`.
+    pub fn difficulty(&self, algorithm: Algorithm) -> Difficulty {
+        Difficulty::from_score(self.score(), algorithm, self.rooms)
+    }
+}
+
+impl<const X: usize, const Y: usize, const Z: usize> MazeGenerator<X, Y, Z> {
+    /// Tries up to `attempts` seeds from `seed` onward until one makes a maze
+    /// of the wanted difficulty, returning that seed. When none do, the maze
+    /// from the last seed tried is kept.
+    pub fn generate_rated(
+        &mut self,
+        algorithm: Algorithm,
+        difficulty: Difficulty,
+        seed: u64,
+        attempts: u64,
+    ) -> Result<Option<u64>, MazeError> {
+        for seed in seed..seed.saturating_add(attempts) {
+            self.try_generate_with(&algorithm, Some(seed))?;
+            let metrics = MazeMetrics::measure(self.maze(), self.start())?;
+            if metrics.difficulty(algorithm) == difficulty {
+                return Ok(Some(seed));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{GameConfig, MazeSize};
+
+    #[test]
+    fn test_metrics() {
+        // An L shaped corridor with one branch: a junction at (1,0,0) and a
+        // dead end at (1,1,0).
+        let mut maze = Maze::<3, 2, 1>::default();
+        let origin = Coord::default();
+        maze.carve_passage(origin, Direction::East);
+        maze.carve_passage(Coord { x: 1, y: 0, z: 0 }, Direction::East);
+        maze.carve_passage(Coord { x: 1, y: 0, z: 0 }, Direction::South);
+        maze.carve_passage(Coord { x: 2, y: 0, z: 0 }, Direction::South);
+        maze.set_door(&Coord { x: 2, y: 1, z: 0 }, Direction::East, true);
+
+        let metrics = MazeMetrics::measure(&maze, origin).expect("metrics");
+        assert_eq!(metrics.solution_length, Some(4));
+        assert_eq!(metrics.junctions, 1);
+        // The origin and (1,1,0) are dead ends; (0,1,0) has no doors at all.
+        assert_eq!(metrics.dead_ends, 2);
+        assert_eq!(metrics.river, 1.0);
+        assert_eq!(metrics.vertical_ratio, 0.0);
+        // Five passages between the two dead ends, the junction and outside.
+        assert_eq!(metrics.average_corridor, 5.0 / 3.0);
+        assert!(MazeMetrics::measure(&maze, Coord { x: 3, y: 0, z: 0 }).is_err());
+    }
+
+    fn count_difficulties<const X: usize, const Y: usize, const Z: usize>(
+        algorithm: Algorithm,
+    ) -> [usize; 3] {
+        let mut counts = [0; 3];
+        for seed in 0..150 {
+            let mut generator = MazeGenerator::<X, Y, Z>::default();
+            generator.generate_with(&algorithm, Some(seed));
+            let metrics =
+                MazeMetrics::measure(generator.maze(), generator.start()).expect("metrics");
+            counts[metrics.difficulty(algorithm) as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_thresholds() {
+        // Each difficulty should be at least a sixth of 150 mazes from seeds
+        // the thresholds weren't calibrated on. The larger sizes are slow to
+        // check for every algorithm, so they're checked for the default and
+        // the ones the game uses there.
+        let mut checks = heapless::Vec::<_, 12>::new();
+        for algorithm in Algorithm::ALL {
+            checks
+                .push((algorithm, count_difficulties::<3, 3, 3>(algorithm)))
+                .unwrap();
+        }
+        for algorithm in [Algorithm::GrowingTree, Algorithm::Prim] {
+            checks
+                .push((algorithm, count_difficulties::<5, 5, 5>(algorithm)))
+                .unwrap();
+            checks
+                .push((algorithm, count_difficulties::<7, 7, 7>(algorithm)))
+                .unwrap();
+        }
+        for (algorithm, counts) in checks {
+            assert!(
+                counts.iter().all(|count| *count >= 25),
+                "{:?} {:?}",
+                algorithm,
+                counts
+            );
+        }
+    }
+
+    fn assert_rated<const X: usize, const Y: usize, const Z: usize>(algorithm: Algorithm) {
+        for difficulty in Difficulty::ALL {
+            let mut generator = MazeGenerator::<X, Y, Z>::default();
+            let seed = generator
+                .generate_rated(algorithm, difficulty, 0, 50)
+                .expect("generate");
+            assert!(
+                seed.is_some(),
+                "no {:?} {}x{}x{} {:?} maze",
+                difficulty,
+                X,
+                Y,
+                Z,
+                algorithm
+            );
+            let metrics =
+                MazeMetrics::measure(generator.maze(), generator.start()).expect("metrics");
+            assert_eq!(metrics.difficulty(algorithm), difficulty);
+        }
+    }
+
+    #[test]
+    fn test_generate_rated() {
+        assert_rated::<3, 3, 3>(Algorithm::default());
+        assert_rated::<5, 5, 5>(Algorithm::default());
+        assert_rated::<7, 7, 7>(Algorithm::default());
+
+        // The presets the game starts each difficulty with.
+        for difficulty in Difficulty::ALL {
+            let config = GameConfig::from(difficulty);
+            match config.size {
+                MazeSize::Small => assert_rated::<3, 3, 3>(config.algorithm),
+                MazeSize::Standard => assert_rated::<5, 5, 5>(config.algorithm),
+                MazeSize::Large => assert_rated::<7, 7, 7>(config.algorithm),
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use embedded_graphics::pixelcolor::{
+    BinaryColor, Gray4, GrayColor, PixelColor, Rgb565, Rgb888, RgbColor, WebColors,
+};
+
+/// How an area is filled in, so areas a display shows in the same color can
+/// still be told apart.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    #[default]
+    Solid,
+    /// Only the edges.
+    Outline,
+    /// Every other pixel.
+    Checkered,
+    /// One pixel in four.
+    Dotted,
+}
+
+/// The colors the game draws with, by what they're used for, so the same
+/// screens can be drawn on color, grayscale and one bit displays.
+pub trait Palette: PixelColor {
+    /// Behind the room.
+    const ROOM: Self;
+    /// The edges of the room.
+    const WALL: Self;
+    /// A door in the room.
+    const DOOR: Self;
+    /// Where a door would be if there was one, which erases any drawn there.
+    const NO_DOOR: Self;
+    /// Behind the status bar, menus and the other screens.
+    const BACKGROUND: Self;
+    const TEXT: Self;
+    const TITLE: Self;
+    /// Menu items that can't be chosen.
+    const DISABLED: Self;
+    /// Filled rooms on maps, and other things in the background of them.
+    const SHADE: Self;
+    /// New high scores, and rooms that were both on the player's route and
+    /// the shortest way out.
+    const HIGHLIGHT: Self;
+    /// The player's route, and doors up and down on the map.
+    const ROUTE: Self;
+    /// The shortest way out, the player on the map and the letter of the
+    /// initials being changed.
+    const MARKER: Self;
+    /// The passages of each floor of the whole maze, from the bottom up.
+    const FLOORS: [Self; 4];
+
+    /// How areas are filled in `SHADE`, `ROUTE` and `MARKER`, for displays
+    /// that can't show them as colors of their own.
+    const SHADE_FILL: Fill = Fill::Solid;
+    const ROUTE_FILL: Fill = Fill::Solid;
+    const MARKER_FILL: Fill = Fill::Solid;
+    /// Menu items that can't be chosen are struck through too, for displays
+    /// where `DISABLED` looks the same as `TEXT`.
+    const STRIKE_DISABLED: bool = false;
+}
+
+macro_rules! rgb_palette {
+    ($color:ty) => {
+        impl Palette for $color {
+            const ROOM: Self = Self::WHITE;
+            const WALL: Self = Self::BLACK;
+            const DOOR: Self = Self::BLACK;
+            const NO_DOOR: Self = Self::WHITE;
+            const BACKGROUND: Self = Self::BLACK;
+            const TEXT: Self = Self::WHITE;
+            const TITLE: Self = Self::YELLOW;
+            const DISABLED: Self = Self::CSS_DIM_GRAY;
+            const SHADE: Self = Self::CSS_DIM_GRAY;
+            const HIGHLIGHT: Self = Self::GREEN;
+            const ROUTE: Self = Self::CYAN;
+            const MARKER: Self = Self::YELLOW;
+            const FLOORS: [Self; 4] = [
+                Self::CSS_CORNFLOWER_BLUE,
+                Self::CSS_MEDIUM_SEA_GREEN,
+                Self::CSS_ORCHID,
+                Self::CSS_CORAL,
+            ];
+        }
+    };
+}
+
+rgb_palette!(Rgb565);
+rgb_palette!(Rgb888);
+
+impl Palette for Gray4 {
+    const ROOM: Self = Self::WHITE;
+    const WALL: Self = Self::BLACK;
+    const DOOR: Self = Self::BLACK;
+    const NO_DOOR: Self = Self::WHITE;
+    const BACKGROUND: Self = Self::BLACK;
+    const TEXT: Self = Self::WHITE;
+    const TITLE: Self = Self::WHITE;
+    const DISABLED: Self = Self::new(8);
+    const SHADE: Self = Self::new(4);
+    const HIGHLIGHT: Self = Self::WHITE;
+    const ROUTE: Self = Self::new(10);
+    const MARKER: Self = Self::new(13);
+    const FLOORS: [Self; 4] = [Self::new(6), Self::new(8), Self::new(10), Self::new(12)];
+}
+
+/// With one bit there's nothing between lit and unlit, so everything that
+/// stands out is lit, and told apart by how it's filled.
+impl Palette for BinaryColor {
+    const ROOM: Self = Self::On;
+    const WALL: Self = Self::Off;
+    const DOOR: Self = Self::Off;
+    const NO_DOOR: Self = Self::On;
+    const BACKGROUND: Self = Self::Off;
+    const TEXT: Self = Self::On;
+    const TITLE: Self = Self::On;
+    const DISABLED: Self = Self::On;
+    const SHADE: Self = Self::On;
+    const HIGHLIGHT: Self = Self::On;
+    const ROUTE: Self = Self::On;
+    const MARKER: Self = Self::On;
+    const FLOORS: [Self; 4] = [Self::On; 4];
+
+    const SHADE_FILL: Fill = Fill::Dotted;
+    const ROUTE_FILL: Fill = Fill::Outline;
+    const MARKER_FILL: Fill = Fill::Checkered;
+    const STRIKE_DISABLED: bool = true;
+}
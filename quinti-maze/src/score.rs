@@ -0,0 +1,309 @@
+use crate::{
+    maze::Coord,
+    metrics::Difficulty,
+    storage::{ByteReader, Storage, StorageKey},
+};
+use heapless::Vec;
+
+/// Counts of what the player did during a game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    /// Moves through a door, including the last one out of the maze.
+    pub moves: u32,
+    /// Tries to move where there was no door.
+    pub bumps: u32,
+    pub turns: u32,
+    pub hints: u32,
+}
+
+/// How well a game went, measured against the shortest way out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub tally: Tally,
+    /// Moves on the shortest way out from the start.
+    pub par: u32,
+    /// Milliseconds spent playing.
+    pub elapsed: u64,
+}
+
+impl Score {
+    pub const PERFECT: u32 = 1000;
+
+    /// Milliseconds allowed for each move at par before the clock costs
+    /// points.
+    const TIME_PER_MOVE: u64 = 3000;
+    const HINT_COST: u32 = 50;
+    const BUMP_COST: u32 = 5;
+
+    /// `PERFECT` for escaping in par, scaled down by any extra moves. Every
+    /// second slower than par time costs a point, as does each hint and bump.
+    pub fn points(&self) -> u32 {
+        let par = self.par.max(1);
+        let base = Self::PERFECT * par / self.tally.moves.max(par);
+        let par_time = par as u64 * Self::TIME_PER_MOVE;
+        let slow_seconds = self.elapsed.saturating_sub(par_time) / 1000;
+        let penalty = u32::try_from(slow_seconds)
+            .unwrap_or(u32::MAX)
+            .saturating_add(self.tally.hints.saturating_mul(Self::HINT_COST))
+            .saturating_add(self.tally.bumps.saturating_mul(Self::BUMP_COST));
+        base.saturating_sub(penalty)
+    }
+}
+
+/// A set of rooms in a maze up to `RoomSet::MAX_SIDE` rooms along each side,
+/// one bit per room.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RoomSet {
+    bits: [u64; 8],
+}
+
+impl RoomSet {
+    pub const MAX_SIDE: usize = 8;
+
+    fn bit(coord: &Coord) -> Option<(usize, u64)> {
+        let side = Self::MAX_SIDE as isize;
+        let inside = |value: isize| (0..side).contains(&value);
+        if !(inside(coord.x) && inside(coord.y) && inside(coord.z)) {
+            return None;
+        }
+        let index = ((coord.z * side + coord.y) * side + coord.x) as usize;
+        Some((index / 64, 1 << (index % 64)))
+    }
+
+    /// Adds `coord`, ignoring rooms outside the largest supported maze.
+    pub fn insert(&mut self, coord: &Coord) {
+        if let Some((word, bit)) = Self::bit(coord) {
+            self.bits[word] |= bit;
+        }
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        Self::bit(coord).is_some_and(|(word, bit)| self.bits[word] & bit != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Everything worth showing about a game once the player is out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GameResult {
+    pub score: Score,
+    pub difficulty: Difficulty,
+    /// Rooms along each side of the maze.
+    pub dimensions: (usize, usize, usize),
+    pub visited: RoomSet,
+    /// The rooms on the shortest way out from the start.
+    pub shortest: RoomSet,
+}
+
+impl GameResult {
+    pub fn room_count(&self) -> usize {
+        let (x, y, z) = self.dimensions;
+        x * y * z
+    }
+}
+
+/// A place on the high score table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighScore {
+    /// Upper case ASCII letters.
+    pub initials: [u8; 3],
+    pub points: u32,
+    /// Whole seconds taken, rounded up.
+    pub seconds: u32,
+}
+
+impl HighScore {
+    pub fn new(initials: [u8; 3], score: &Score) -> Self {
+        Self {
+            initials,
+            points: score.points(),
+            seconds: u32::try_from(score.elapsed.div_ceil(1000)).unwrap_or(u32::MAX),
+        }
+    }
+
+    pub fn initials(&self) -> &str {
+        core::str::from_utf8(&self.initials).unwrap_or("???")
+    }
+}
+
+const HIGH_SCORES_MAGIC: &[u8] = b"QMH";
+const HIGH_SCORES_VERSION: u8 = 1;
+const HIGH_SCORE_LEN: usize = 11;
+pub const HIGH_SCORES_MAX_LEN: usize =
+    HIGH_SCORES_MAGIC.len() + 1 + Difficulty::ALL.len() * (1 + HighScores::PLACES * HIGH_SCORE_LEN);
+
+/// The best scores for each difficulty, best first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HighScores {
+    tables: [Vec<HighScore, { HighScores::PLACES }>; Difficulty::ALL.len()],
+}
+
+impl HighScores {
+    pub const PLACES: usize = 10;
+
+    pub fn table(&self, difficulty: Difficulty) -> &[HighScore] {
+        &self.tables[difficulty as usize]
+    }
+
+    /// The place `points` would take on the table, if it makes it on.
+    pub fn place(&self, difficulty: Difficulty, points: u32) -> Option<usize> {
+        let table = self.table(difficulty);
+        let place = table
+            .iter()
+            .take_while(|entry| entry.points >= points)
+            .count();
+        (points > 0 && place < Self::PLACES).then_some(place)
+    }
+
+    /// Adds `entry` below any equal scores, returning its place.
+    pub fn insert(&mut self, difficulty: Difficulty, entry: HighScore) -> Option<usize> {
+        let place = self.place(difficulty, entry.points)?;
+        let table = &mut self.tables[difficulty as usize];
+        if table.is_full() {
+            table.pop();
+        }
+        table.insert(place, entry).ok()?;
+        Some(place)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8, HIGH_SCORES_MAX_LEN> {
+        let mut bytes = Vec::new();
+        let mut write = |data: &[u8]| bytes.extend_from_slice(data).expect("high scores");
+        write(HIGH_SCORES_MAGIC);
+        write(&[HIGH_SCORES_VERSION]);
+        for table in &self.tables {
+            write(&[table.len() as u8]);
+            for entry in table {
+                write(&entry.initials);
+                write(&entry.points.to_le_bytes());
+                write(&entry.seconds.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader::new(data);
+        if reader.bytes(HIGH_SCORES_MAGIC.len())? != HIGH_SCORES_MAGIC
+            || reader.u8()? != HIGH_SCORES_VERSION
+        {
+            return None;
+        }
+        let mut high_scores = Self::default();
+        for table in &mut high_scores.tables {
+            for _ in 0..reader.u8()? {
+                let initials: [u8; 3] = reader.bytes(3)?.try_into().ok()?;
+                if !initials.iter().all(u8::is_ascii_uppercase) {
+                    return None;
+                }
+                let entry = HighScore {
+                    initials,
+                    points: reader.u32()?,
+                    seconds: reader.u32()?,
+                };
+                table.push(entry).ok()?;
+            }
+        }
+        reader.is_empty().then_some(high_scores)
+    }
+
+    /// Reads the table kept in `storage`, which is empty if there isn't one
+    /// or it can't be read.
+    pub fn load(storage: &mut dyn Storage) -> Self {
+        let mut buffer = [0; HIGH_SCORES_MAX_LEN];
+        storage
+            .read(StorageKey::HighScores, &mut buffer)
+            .and_then(|len| Self::from_bytes(&buffer[..len]))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) -> bool {
+        storage.write(StorageKey::HighScores, &self.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_points() {
+        let mut score = Score {
+            tally: Tally {
+                moves: 14,
+                ..Default::default()
+            },
+            par: 14,
+            elapsed: 14 * 3000,
+        };
+        assert_eq!(score.points(), Score::PERFECT);
+
+        score.tally.moves = 28;
+        assert_eq!(score.points(), 500);
+
+        score.tally.bumps = 2;
+        score.tally.hints = 1;
+        score.elapsed += 10_000;
+        assert_eq!(score.points(), 500 - 10 - 50 - 10);
+
+        score.elapsed = u64::MAX;
+        assert_eq!(score.points(), 0);
+    }
+
+    #[test]
+    fn test_room_set() {
+        let mut rooms = RoomSet::default();
+        let corner = Coord { x: 7, y: 7, z: 7 };
+        rooms.insert(&Coord::default());
+        rooms.insert(&corner);
+        rooms.insert(&corner);
+        rooms.insert(&Coord { x: 8, y: 0, z: 0 });
+        assert_eq!(rooms.len(), 2);
+        assert!(rooms.contains(&corner));
+        assert!(!rooms.contains(&Coord { x: 0, y: 0, z: 1 }));
+        assert!(!rooms.contains(&Coord { x: -1, y: 0, z: 0 }));
+    }
+
+    #[test]
+    fn test_high_scores() {
+        let entry = |points| HighScore {
+            initials: *b"ABC",
+            points,
+            seconds: 60,
+        };
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.place(Difficulty::Hard, 0), None);
+        for points in 1..=HighScores::PLACES as u32 {
+            high_scores.insert(Difficulty::Hard, entry(points * 100));
+        }
+        assert_eq!(high_scores.place(Difficulty::Hard, 100), None);
+        assert_eq!(high_scores.place(Difficulty::Easy, 100), Some(0));
+
+        // Ties go below the score already there, pushing the last one off.
+        assert_eq!(high_scores.insert(Difficulty::Hard, entry(500)), Some(6));
+        let table = high_scores.table(Difficulty::Hard);
+        assert_eq!(table.len(), HighScores::PLACES);
+        assert_eq!(table[0].points, 1000);
+        assert_eq!(table[HighScores::PLACES - 1].points, 200);
+
+        let mut storage = MemoryStorage::<HIGH_SCORES_MAX_LEN>::default();
+        assert_eq!(HighScores::load(&mut storage), HighScores::default());
+        assert!(high_scores.save(&mut storage));
+        assert_eq!(HighScores::load(&mut storage), high_scores);
+
+        let mut bytes = high_scores.to_bytes();
+        bytes.pop();
+        assert_eq!(HighScores::from_bytes(&bytes), None);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::game::Note;
+
+/// What to do to the speaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneAction {
+    /// Start sounding a tone, in place of any already sounding.
+    On {
+        frequency: u64,
+    },
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Start the note at this index.
+    Start(usize),
+    /// Stop the tone, then wait to start the note at this index.
+    Stop(usize),
+}
+
+/// Plays a tune without blocking: the platform asks for the tone actions
+/// due at the time it's called, using the same millisecond ticks as the
+/// game, and comes back at `next_due`. Each note waits for its delay after
+/// the one before stops, then sounds for its duration.
+#[derive(Debug, Default)]
+pub struct Sequencer {
+    tune: &'static [Note],
+    /// The next step and the ticks it's due at.
+    next: Option<(u64, Step)>,
+    sounding: bool,
+}
+
+impl Sequencer {
+    /// Starts `tune` from `now`, cutting short whatever was playing.
+    pub fn play(&mut self, tune: &'static [Note], now: u64) {
+        self.tune = tune;
+        self.next = if self.sounding {
+            Some((now, Step::Stop(0)))
+        } else {
+            self.start_after(now, 0)
+        };
+    }
+
+    /// Stops playing, silencing the tone on the next `update`.
+    pub fn stop(&mut self, now: u64) {
+        self.next = self.sounding.then_some((now, Step::Stop(self.tune.len())));
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// The ticks the next action is due at, if there is one.
+    pub fn next_due(&self) -> Option<u64> {
+        self.next.map(|(due, _)| due)
+    }
+
+    /// The next action due by `now`, if any. Call it until it returns
+    /// `None` to catch up on everything due.
+    pub fn update(&mut self, now: u64) -> Option<ToneAction> {
+        let (due, step) = self.next.filter(|&(due, _)| due <= now)?;
+        match step {
+            Step::Start(index) => {
+                let note = &self.tune[index];
+                self.next = Some((due + note.duration as u64, Step::Stop(index + 1)));
+                self.sounding = true;
+                Some(ToneAction::On {
+                    frequency: note.frequency,
+                })
+            }
+            Step::Stop(index) => {
+                self.next = self.start_after(due, index);
+                self.sounding = false;
+                Some(ToneAction::Off)
+            }
+        }
+    }
+
+    fn start_after(&self, ticks: u64, index: usize) -> Option<(u64, Step)> {
+        self.tune
+            .get(index)
+            .map(|note| (ticks + note.delay, Step::Start(index)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TUNE: &[Note] = &[Note::new(1000, 100, 0), Note::new(1333, 50, 20)];
+
+    #[test]
+    fn test_sequencer() {
+        let mut sequencer = Sequencer::default();
+        assert_eq!(sequencer.update(0), None);
+
+        sequencer.play(TUNE, 1000);
+        assert_eq!(
+            sequencer.update(1000),
+            Some(ToneAction::On { frequency: 1000 })
+        );
+        assert_eq!(sequencer.update(1000), None);
+        assert_eq!(sequencer.next_due(), Some(1100));
+
+        // Late updates catch up without drifting.
+        assert_eq!(sequencer.update(1130), Some(ToneAction::Off));
+        assert_eq!(
+            sequencer.update(1130),
+            Some(ToneAction::On { frequency: 1333 })
+        );
+        assert_eq!(sequencer.next_due(), Some(1170));
+        assert_eq!(sequencer.update(1170), Some(ToneAction::Off));
+        assert!(!sequencer.is_playing());
+        assert_eq!(sequencer.update(2000), None);
+    }
+
+    #[test]
+    fn test_interrupt() {
+        let mut sequencer = Sequencer::default();
+        sequencer.play(TUNE, 0);
+        sequencer.update(0);
+
+        // A new tune silences the old one first.
+        sequencer.play(&TUNE[1..], 50);
+        assert_eq!(sequencer.update(50), Some(ToneAction::Off));
+        assert_eq!(sequencer.update(60), None);
+        assert_eq!(
+            sequencer.update(70),
+            Some(ToneAction::On { frequency: 1333 })
+        );
+
+        sequencer.stop(80);
+        assert_eq!(sequencer.update(80), Some(ToneAction::Off));
+        assert!(!sequencer.is_playing());
+
+        sequencer.stop(90);
+        assert_eq!(sequencer.update(90), None);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{game::SNAPSHOT_MAX_LEN, score::HIGH_SCORES_MAX_LEN};
+use heapless::Vec;
+
+/// The things the game keeps between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKey {
+    SavedGame,
+    HighScores,
+}
+
+impl StorageKey {
+    /// The most that's ever stored for this key.
+    pub const fn max_len(&self) -> usize {
+        match self {
+            Self::SavedGame => SNAPSHOT_MAX_LEN,
+            Self::HighScores => HIGH_SCORES_MAX_LEN,
+        }
+    }
+
+    /// A short name, suitable for a file name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SavedGame => "saved-game",
+            Self::HighScores => "high-scores",
+        }
+    }
+}
+
+/// Byte oriented storage supplied by the platform, such as files on a
+/// computer or a flash page on the device.
+pub trait Storage {
+    /// Reads the data stored for `key` into `buffer`, returning its length, or
+    /// `None` if nothing is stored or it doesn't fit.
+    fn read(&mut self, key: StorageKey, buffer: &mut [u8]) -> Option<usize>;
+
+    /// Replaces the data stored for `key`, returning false on failure.
+    fn write(&mut self, key: StorageKey, data: &[u8]) -> bool;
+
+    /// Forgets the data stored for `key`, returning false on failure.
+    fn remove(&mut self, key: StorageKey) -> bool;
+
+    /// Whether anything is stored for `key`. This reads it all unless the
+    /// platform has a cheaper way to tell.
+    fn exists(&mut self, key: StorageKey) -> bool {
+        let mut buffer = [0; MAX_LEN];
+        self.read(key, &mut buffer[..key.max_len()]).is_some()
+    }
+}
+
+/// The most stored for any key.
+const MAX_LEN: usize = if SNAPSHOT_MAX_LEN > HIGH_SCORES_MAX_LEN {
+    SNAPSHOT_MAX_LEN
+} else {
+    HIGH_SCORES_MAX_LEN
+};
+
+/// Storage that only lasts as long as it does, holding up to `N` bytes for
+/// each key.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage<const N: usize> {
+    entries: Vec<(StorageKey, Vec<u8, N>), 4>,
+}
+
+impl<const N: usize> Storage for MemoryStorage<N> {
+    fn read(&mut self, key: StorageKey, buffer: &mut [u8]) -> Option<usize> {
+        let (_, data) = self
+            .entries
+            .iter()
+            .find(|(entry_key, _)| *entry_key == key)?;
+        buffer.get_mut(..data.len())?.copy_from_slice(data);
+        Some(data.len())
+    }
+
+    fn write(&mut self, key: StorageKey, data: &[u8]) -> bool {
+        let data = match Vec::from_slice(data) {
+            Ok(data) => data,
+            Err(_) => return false,
+        };
+        self.remove(key);
+        self.entries.push((key, data)).is_ok()
+    }
+
+    fn remove(&mut self, key: StorageKey) -> bool {
+        self.entries.retain(|(entry_key, _)| *entry_key != key);
+        true
+    }
+
+    fn exists(&mut self, key: StorageKey) -> bool {
+        self.entries.iter().any(|(entry_key, _)| *entry_key == key)
+    }
+}
+
+/// Reads little endian values from the front of a byte slice.
+#[derive(Debug)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.data.len() {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    pub fn i8(&mut self) -> Option<i8> {
+        self.u8().map(|value| value as i8)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        let bytes = self.bytes(8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_memory_storage() {
+        let mut storage = MemoryStorage::<4>::default();
+        let mut buffer = [0; 4];
+        assert_eq!(storage.read(StorageKey::SavedGame, &mut buffer), None);
+
+        assert!(storage.write(StorageKey::SavedGame, &[1, 2, 3]));
+        assert!(storage.write(StorageKey::SavedGame, &[4, 5]));
+        assert_eq!(storage.read(StorageKey::SavedGame, &mut buffer), Some(2));
+        assert_eq!(buffer[..2], [4, 5]);
+        assert!(storage.exists(StorageKey::SavedGame));
+        assert!(!storage.exists(StorageKey::HighScores));
+
+        assert!(!storage.write(StorageKey::SavedGame, &[0; 5]));
+        assert!(storage.remove(StorageKey::SavedGame));
+        assert_eq!(storage.read(StorageKey::SavedGame, &mut buffer), None);
+        assert!(!storage.exists(StorageKey::SavedGame));
+    }
+
+    /// Storage with only the methods it has to have.
+    struct ReadOnly(MemoryStorage<4>);
+
+    impl Storage for ReadOnly {
+        fn read(&mut self, key: StorageKey, buffer: &mut [u8]) -> Option<usize> {
+            self.0.read(key, buffer)
+        }
+
+        fn write(&mut self, _key: StorageKey, _data: &[u8]) -> bool {
+            false
+        }
+
+        fn remove(&mut self, _key: StorageKey) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_exists_by_reading() {
+        let mut inner = MemoryStorage::default();
+        inner.write(StorageKey::HighScores, &[1, 2, 3, 4]);
+        let mut storage = ReadOnly(inner);
+        assert!(storage.exists(StorageKey::HighScores));
+        assert!(!storage.exists(StorageKey::SavedGame));
+    }
+}
@@ -1,21 +1,31 @@
 #![no_std]
-use core::{
-    fmt::{Debug, Error, Formatter},
-    time::Duration,
-};
-use embedded_graphics::pixelcolor::Rgb565;
+extern crate std;
+
+use core::fmt::{Debug, Error, Formatter};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
 use embedded_graphics_simulator::{
-    sdl2::Keycode, OutputSettings, SimulatorDisplay, SimulatorEvent, Window,
+    sdl2::{Keycode, Mod},
+    OutputSettings, SimulatorDisplay, SimulatorEvent, Window,
 };
 use quinti_maze::{
-    draw::SCREEN_SIZE,
-    game::{Command, Game, PlatformSpecific, NOTES},
+    draw::Viewport,
+    game::{Game, Input, Key, PlatformSpecific, SoundEvent},
+    keys::{KeyBindings, KeyId, Layout},
+    sequencer::{Sequencer, ToneAction},
+    storage::Storage,
     time::Timer,
 };
 use rodio::{source::SineWave, OutputStream, OutputStreamHandle, Sink, Source};
+use storage::FileStorage;
+
+mod storage;
 
 struct SimPlatform {
     timer: Timer,
+    storage: FileStorage,
+    sequencer: Sequencer,
+    /// The tone sounding, which stops when it's dropped.
+    tone: Option<Sink>,
     #[allow(unused)]
     stream: OutputStream,
     #[allow(unused)]
@@ -27,6 +37,9 @@ impl Default for SimPlatform {
         let (stream, stream_handle) = OutputStream::try_default().expect("default sound output");
         Self {
             timer: Timer::default(),
+            storage: FileStorage::default(),
+            sequencer: Sequencer::default(),
+            tone: None,
             stream,
             stream_handle,
         }
@@ -37,37 +50,133 @@ impl Debug for SimPlatform {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         f.debug_struct("SimPlatform")
             .field("timer", &self.timer)
+            .field("storage", &self.storage)
+            .field("sequencer", &self.sequencer)
             .finish()
     }
 }
 
 impl PlatformSpecific for SimPlatform {
-    fn play_victory_notes(&mut self) {
-        let sink = Sink::try_new(&self.stream_handle).expect("new sink");
-        for note in NOTES {
-            let source = SineWave::new(note.frequency as u32)
-                .take_duration(Duration::from_millis(note.duration as u64))
-                .amplify(0.20)
-                .delay(Duration::from_millis(note.delay));
-            sink.append(source);
-        }
-        sink.sleep_until_end();
+    fn play_sound(&mut self, event: SoundEvent) {
+        self.sequencer.play(event.tune(), self.timer.elapsed());
     }
 
     fn ticks(&mut self) -> u64 {
         self.timer.elapsed()
     }
+
+    fn storage(&mut self) -> Option<&mut dyn Storage> {
+        Some(&mut self.storage)
+    }
+}
+
+impl SimPlatform {
+    /// Starts and stops tones as the tune playing calls for.
+    fn update_sound(&mut self) {
+        while let Some(action) = self.sequencer.update(self.timer.elapsed()) {
+            self.tone = match action {
+                ToneAction::On { frequency } => {
+                    let sink = Sink::try_new(&self.stream_handle).expect("new sink");
+                    sink.append(SineWave::new(frequency as u32).amplify(0.20));
+                    Some(sink)
+                }
+                ToneAction::Off => None,
+            };
+        }
+    }
+}
+
+/// The key a keycode is, with shift giving the characters on a US keyboard
+/// that the BASIC layout uses.
+fn key_id(keycode: Keycode, keymod: Mod) -> Option<KeyId> {
+    let shift = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+    match keycode {
+        Keycode::Left => Some(KeyId::Left),
+        Keycode::Right => Some(KeyId::Right),
+        Keycode::Up => Some(KeyId::Up),
+        Keycode::Down => Some(KeyId::Down),
+        Keycode::Slash if shift => Some(KeyId::Character('?')),
+        Keycode::Num8 if shift => Some(KeyId::Character('*')),
+        _ => {
+            let name = keycode.name();
+            let mut characters = name.chars();
+            match (characters.next(), characters.next()) {
+                (Some(character), None) => Some(KeyId::character(character)),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The character a key types, for entering initials.
+fn typed_character(keycode: Keycode) -> Option<char> {
+    match keycode {
+        Keycode::Return => Some('\n'),
+        Keycode::Backspace => Some('\u{8}'),
+        _ => {
+            let name = keycode.name();
+            let mut characters = name.chars();
+            match (characters.next(), characters.next()) {
+                (Some(letter), None) if letter.is_ascii_alphabetic() => Some(letter),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// How long a key is held for a long press.
+const LONG_PRESS_MILLIS: u64 = 1000;
+
+fn key_for(bindings: &KeyBindings, keycode: Keycode, keymod: Mod) -> Key {
+    Key {
+        command: key_id(keycode, keymod).and_then(|key| bindings.command(key)),
+        character: typed_character(keycode),
+    }
+}
+
+/// The keyboard layout, changed by any `keys.txt` in the data directory.
+fn key_bindings() -> KeyBindings {
+    let mut bindings = KeyBindings::new(Layout::Keyboard);
+    let path = storage::data_dir().map(|directory| directory.join("keys.txt"));
+    if let Some(text) = path.and_then(|path| std::fs::read_to_string(path).ok()) {
+        if let Err(error) = bindings.load(&text) {
+            std::eprintln!("keys.txt: {:?}", error);
+        }
+    }
+    bindings
+}
+
+/// The size of the window from a `WIDTHxHEIGHT` argument, or else the size
+/// of the device's screen.
+fn window_size() -> Size {
+    std::env::args()
+        .nth(1)
+        .and_then(|argument| {
+            let (width, height) = argument.split_once('x')?;
+            Some(Size::new(width.parse().ok()?, height.parse().ok()?))
+        })
+        .unwrap_or(Viewport::LCD.size)
 }
 
 fn main() -> Result<(), core::convert::Infallible> {
-    let mut display = SimulatorDisplay::<Rgb565>::new(SCREEN_SIZE);
+    let size = window_size();
+    let mut display = SimulatorDisplay::<Rgb565>::new(size);
 
+    let bindings = key_bindings();
     let mut game = Game::<SimPlatform>::new();
+    game.set_viewport(Viewport::new(Point::zero(), size));
+    game.show_menu();
 
     let output_settings = OutputSettings::default();
     let mut window = Window::new("Quinti-Maze", &output_settings);
 
+    // The key held down, when it went down, and whether it's been long
+    // pressed yet.
+    let timer = Timer::default();
+    let mut held: Option<(Keycode, u64, bool)> = None;
+
     loop {
+        game.platform_mut().update_sound();
         game.draw(&mut display)?;
 
         window.update(&display);
@@ -75,42 +184,40 @@ fn main() -> Result<(), core::convert::Infallible> {
         for event in window.events() {
             match event {
                 SimulatorEvent::Quit => {
+                    game.save_game().ok();
                     return Ok(());
                 }
-                SimulatorEvent::KeyDown { keycode, .. } => {
-                    if game.key_hit() {
-                        match keycode {
-                            Keycode::W => {
-                                game.handle_command(Command::MoveForward);
-                            }
-                            Keycode::D => {
-                                game.handle_command(Command::MoveRight);
-                            }
-                            Keycode::A => {
-                                game.handle_command(Command::MoveLeft);
-                            }
-                            Keycode::E => {
-                                game.handle_command(Command::MoveUp);
-                            }
-                            Keycode::Q => {
-                                game.handle_command(Command::MoveDown);
-                            }
-                            Keycode::Left => {
-                                game.handle_command(Command::TurnLeft);
-                            }
-                            Keycode::Right => {
-                                game.handle_command(Command::TurnRight);
-                            }
-                            Keycode::Slash => {
-                                game.handle_command(Command::ToggleShowPosition);
-                            }
-                            Keycode::Equals => {
-                                game.handle_command(Command::ShowHints);
-                            }
-                            _ => {}
+                SimulatorEvent::KeyDown {
+                    keycode,
+                    keymod,
+                    repeat,
+                } => {
+                    let key = key_for(&bindings, keycode, keymod);
+                    let now = timer.elapsed();
+                    if !repeat {
+                        held = Some((keycode, now, false));
+                        game.handle_input(Input::KeyDown(key));
+                        continue;
+                    }
+                    game.handle_input(Input::Repeat(key));
+                    if let Some((held_keycode, down, long_pressed)) = &mut held {
+                        if *held_keycode == keycode
+                            && !*long_pressed
+                            && now - *down >= LONG_PRESS_MILLIS
+                        {
+                            *long_pressed = true;
+                            game.handle_input(Input::LongPress(key));
                         }
                     }
                 }
+                SimulatorEvent::KeyUp {
+                    keycode, keymod, ..
+                } => {
+                    if held.is_some_and(|(held_keycode, ..)| held_keycode == keycode) {
+                        held = None;
+                    }
+                    game.handle_input(Input::KeyUp(key_for(&bindings, keycode, keymod)));
+                }
                 _ => (),
             }
         }
@@ -0,0 +1,69 @@
+extern crate std;
+
+use quinti_maze::storage::{Storage, StorageKey};
+use std::{env, fs, io::ErrorKind, path::PathBuf};
+
+/// Keeps each storage key in its own file in the user's data directory.
+#[derive(Debug)]
+pub struct FileStorage {
+    directory: Option<PathBuf>,
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        Self {
+            directory: data_dir(),
+        }
+    }
+}
+
+/// Where the game keeps its files, if there's anywhere.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.map(|base| base.join("quinti-maze"))
+}
+
+impl FileStorage {
+    fn path(&self, key: StorageKey) -> Option<PathBuf> {
+        self.directory
+            .as_ref()
+            .map(|directory| directory.join(key.name()).with_extension("bin"))
+    }
+}
+
+impl Storage for FileStorage {
+    fn read(&mut self, key: StorageKey, buffer: &mut [u8]) -> Option<usize> {
+        let data = fs::read(self.path(key)?).ok()?;
+        buffer.get_mut(..data.len())?.copy_from_slice(&data);
+        Some(data.len())
+    }
+
+    fn write(&mut self, key: StorageKey, data: &[u8]) -> bool {
+        match (self.directory.as_ref(), self.path(key)) {
+            (Some(directory), Some(path)) => {
+                fs::create_dir_all(directory).is_ok() && fs::write(path, data).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    fn remove(&mut self, key: StorageKey) -> bool {
+        match self.path(key).map(fs::remove_file) {
+            Some(Ok(())) => true,
+            Some(Err(error)) => error.kind() == ErrorKind::NotFound,
+            None => false,
+        }
+    }
+
+    fn exists(&mut self, key: StorageKey) -> bool {
+        self.path(key).is_some_and(|path| path.is_file())
+    }
+}